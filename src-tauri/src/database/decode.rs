@@ -0,0 +1,323 @@
+use serde_json::Value;
+use sqlx::mysql::types::MySqlTime;
+use sqlx::mysql::{MySql, MySqlColumn, MySqlRow};
+use sqlx::postgres::types::{Oid, PgInterval, PgMoney, PgTimeTz};
+use sqlx::postgres::{PgColumn, PgConnection, PgHasArrayType, PgRow, Postgres};
+use sqlx::types::chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use sqlx::types::ipnetwork::IpNetwork;
+use sqlx::types::{BigDecimal, Uuid};
+use sqlx::{Column as _, Decode, Row, Type, TypeInfo, ValueRef};
+
+use crate::types::ResultColumn;
+
+// Integers beyond this can't round-trip through a JavaScript number
+const MAX_SAFE_INTEGER: i64 = (1 << 53) - 1;
+
+fn int_to_json(v: i64) -> Value {
+    if (-MAX_SAFE_INTEGER..=MAX_SAFE_INTEGER).contains(&v) {
+        Value::Number(v.into())
+    } else {
+        Value::String(v.to_string())
+    }
+}
+
+fn uint_to_json(v: u64) -> Value {
+    if v <= MAX_SAFE_INTEGER as u64 {
+        Value::Number(v.into())
+    } else {
+        Value::String(v.to_string())
+    }
+}
+
+fn float_to_json(v: f64) -> Value {
+    // NaN and infinities have no JSON number form
+    serde_json::Number::from_f64(v)
+        .map(Value::Number)
+        .unwrap_or_else(|| Value::String(v.to_string()))
+}
+
+fn hex_string(prefix: &str, bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(prefix.len() + bytes.len() * 2);
+    out.push_str(prefix);
+    for b in bytes {
+        out.push_str(&format!("{:02x}", b));
+    }
+    out
+}
+
+fn format_timestamp(v: NaiveDateTime) -> String {
+    v.format("%Y-%m-%d %H:%M:%S%.f").to_string()
+}
+
+// Same shape as Postgres' default `IntervalStyle`: "1 year 2 mons 3 days 04:05:06.5"
+fn format_interval(interval: &PgInterval) -> String {
+    let mut parts = Vec::new();
+    let years = interval.months / 12;
+    let months = interval.months % 12;
+    if years != 0 {
+        parts.push(format!("{} year{}", years, if years.abs() == 1 { "" } else { "s" }));
+    }
+    if months != 0 {
+        parts.push(format!("{} mon{}", months, if months.abs() == 1 { "" } else { "s" }));
+    }
+    if interval.days != 0 {
+        parts.push(format!("{} day{}", interval.days, if interval.days.abs() == 1 { "" } else { "s" }));
+    }
+
+    let micros = interval.microseconds;
+    if micros != 0 || parts.is_empty() {
+        let sign = if micros < 0 { "-" } else { "" };
+        let micros = micros.unsigned_abs();
+        let hours = micros / 3_600_000_000;
+        let minutes = micros / 60_000_000 % 60;
+        let seconds = micros / 1_000_000 % 60;
+        let fraction = micros % 1_000_000;
+        let mut time = format!("{}{:02}:{:02}:{:02}", sign, hours, minutes, seconds);
+        if fraction != 0 {
+            let digits = format!("{:06}", fraction);
+            time.push('.');
+            time.push_str(digits.trim_end_matches('0'));
+        }
+        parts.push(time);
+    }
+
+    parts.join(" ")
+}
+
+fn get<'r, T>(row: &'r PgRow, index: usize, render: impl Fn(T) -> Value) -> Option<Value>
+where
+    T: Decode<'r, Postgres> + Type<Postgres>,
+{
+    row.try_get::<T, _>(index).ok().map(render)
+}
+
+fn get_array<T>(row: &PgRow, index: usize, render: impl Fn(T) -> Value) -> Option<Value>
+where
+    T: for<'r> Decode<'r, Postgres> + Type<Postgres> + PgHasArrayType,
+{
+    row.try_get::<Vec<Option<T>>, _>(index).ok().map(|items| {
+        Value::Array(
+            items
+                .into_iter()
+                .map(|item| item.map(&render).unwrap_or(Value::Null))
+                .collect(),
+        )
+    })
+}
+
+fn decode_postgres(row: &PgRow, index: usize, type_name: &str) -> Option<Value> {
+    if let Some(element) = type_name.strip_suffix("[]") {
+        return match element {
+            "BOOL" => get_array(row, index, Value::Bool),
+            "INT2" => get_array(row, index, |v: i16| int_to_json(v.into())),
+            "INT4" => get_array(row, index, |v: i32| int_to_json(v.into())),
+            "INT8" => get_array(row, index, int_to_json),
+            "FLOAT4" => get_array(row, index, |v: f32| float_to_json(v.into())),
+            "FLOAT8" => get_array(row, index, float_to_json),
+            "NUMERIC" => get_array(row, index, |v: BigDecimal| Value::String(v.to_string())),
+            "TEXT" | "VARCHAR" | "BPCHAR" | "NAME" => get_array(row, index, Value::String),
+            "UUID" => get_array(row, index, |v: Uuid| Value::String(v.to_string())),
+            "JSON" | "JSONB" => get_array(row, index, |v: Value| v),
+            "DATE" => get_array(row, index, |v: NaiveDate| Value::String(v.to_string())),
+            "TIMESTAMP" => get_array(row, index, |v: NaiveDateTime| Value::String(format_timestamp(v))),
+            "TIMESTAMPTZ" => get_array(row, index, |v: DateTime<Utc>| {
+                Value::String(v.format("%Y-%m-%d %H:%M:%S%.f%:z").to_string())
+            }),
+            "INET" | "CIDR" => get_array(row, index, |v: IpNetwork| Value::String(v.to_string())),
+            _ => None,
+        };
+    }
+
+    match type_name {
+        "BOOL" => get(row, index, Value::Bool),
+        "INT2" => get(row, index, |v: i16| int_to_json(v.into())),
+        "INT4" => get(row, index, |v: i32| int_to_json(v.into())),
+        "INT8" => get(row, index, int_to_json),
+        "OID" => get(row, index, |v: Oid| uint_to_json(v.0.into())),
+        "FLOAT4" => get(row, index, |v: f32| float_to_json(v.into())),
+        "FLOAT8" => get(row, index, float_to_json),
+        // Rendered as strings so no digits are lost on the way to JavaScript
+        "NUMERIC" => get(row, index, |v: BigDecimal| Value::String(v.to_string())),
+        "MONEY" => get(row, index, |v: PgMoney| Value::String(v.to_bigdecimal(2).to_string())),
+        "UUID" => get(row, index, |v: Uuid| Value::String(v.to_string())),
+        "JSON" | "JSONB" => get(row, index, |v: Value| v),
+        "BYTEA" => get(row, index, |v: Vec<u8>| Value::String(hex_string("\\x", &v))),
+        "DATE" => get(row, index, |v: NaiveDate| Value::String(v.to_string())),
+        "TIME" => get(row, index, |v: NaiveTime| Value::String(v.to_string())),
+        "TIMETZ" => get(row, index, |v: PgTimeTz<NaiveTime, FixedOffset>| {
+            Value::String(format!("{}{}", v.time, v.offset))
+        }),
+        "TIMESTAMP" => get(row, index, |v: NaiveDateTime| Value::String(format_timestamp(v))),
+        "TIMESTAMPTZ" => get(row, index, |v: DateTime<Utc>| {
+            Value::String(v.format("%Y-%m-%d %H:%M:%S%.f%:z").to_string())
+        }),
+        "INTERVAL" => get(row, index, |v: PgInterval| Value::String(format_interval(&v))),
+        // A host address is shown without its /32 or /128 suffix, like psql does
+        "INET" => get(row, index, |v: IpNetwork| {
+            if v.prefix() == if v.is_ipv4() { 32 } else { 128 } {
+                Value::String(v.ip().to_string())
+            } else {
+                Value::String(v.to_string())
+            }
+        }),
+        "CIDR" => get(row, index, |v: IpNetwork| Value::String(v.to_string())),
+        "VOID" => Some(Value::Null),
+        _ => None,
+    }
+}
+
+/// Renders one result cell using the column's Postgres type. Types without a dedicated
+/// decoder (enums, citext, domains, ...) fall back to their text form, then to hex.
+pub fn postgres_value_to_json(row: &PgRow, index: usize) -> Value {
+    let raw = match row.try_get_raw(index) {
+        Ok(raw) => raw,
+        Err(_) => return Value::Null,
+    };
+    if raw.is_null() {
+        return Value::Null;
+    }
+    let type_name = raw.type_info().name().to_string();
+
+    if let Some(value) = decode_postgres(row, index, &type_name) {
+        return value;
+    }
+
+    match raw.as_bytes() {
+        Ok(bytes) => match std::str::from_utf8(bytes) {
+            Ok(text) => Value::String(text.to_string()),
+            Err(_) => Value::String(hex_string("\\x", bytes)),
+        },
+        Err(_) => Value::Null,
+    }
+}
+
+/// Column metadata for a Postgres result. Nullability is looked up in `pg_attribute` for
+/// columns that come straight from a table; computed columns report `None`.
+pub async fn postgres_result_columns(conn: &mut PgConnection, columns: &[PgColumn]) -> Vec<ResultColumn> {
+    let sources: Vec<(i64, i16)> = columns
+        .iter()
+        .filter_map(|c| Some((c.relation_id()?.0 as i64, c.relation_attribute_no()?)))
+        .collect();
+
+    let mut not_null: Vec<(i64, i16, bool)> = Vec::new();
+    if !sources.is_empty() {
+        let (rel_ids, att_nos): (Vec<i64>, Vec<i16>) = sources.into_iter().unzip();
+        // Best effort: a failure here (e.g. inside an aborted transaction) just leaves nullability unknown
+        if let Ok(rows) = sqlx::query(
+            r#"
+            SELECT a.attrelid::int8 AS rel_id, a.attnum, a.attnotnull
+            FROM pg_attribute a
+            JOIN unnest($1::int8[], $2::int2[]) AS c(rel_id, att_no)
+                ON a.attrelid = c.rel_id::oid AND a.attnum = c.att_no
+            "#,
+        )
+        .bind(&rel_ids)
+        .bind(&att_nos)
+        .fetch_all(&mut *conn)
+        .await
+        {
+            not_null = rows
+                .iter()
+                .map(|row| (row.get("rel_id"), row.get("attnum"), row.get("attnotnull")))
+                .collect();
+        }
+    }
+
+    columns
+        .iter()
+        .map(|c| {
+            let nullable = match (c.relation_id(), c.relation_attribute_no()) {
+                (Some(rel_id), Some(att_no)) => not_null
+                    .iter()
+                    .find(|(r, a, _)| *r == rel_id.0 as i64 && *a == att_no)
+                    .map(|(_, _, not_null)| !not_null),
+                _ => None,
+            };
+            ResultColumn {
+                name: c.name().to_string(),
+                db_type: c.type_info().name().to_string(),
+                nullable,
+            }
+        })
+        .collect()
+}
+
+fn get_mysql<'r, T>(row: &'r MySqlRow, index: usize, render: impl Fn(T) -> Value) -> Option<Value>
+where
+    T: Decode<'r, MySql> + Type<MySql>,
+{
+    row.try_get::<T, _>(index).ok().map(render)
+}
+
+fn decode_mysql(row: &MySqlRow, index: usize, type_name: &str) -> Option<Value> {
+    match type_name {
+        "BOOLEAN" => get_mysql(row, index, Value::Bool),
+        "TINYINT" | "SMALLINT" | "MEDIUMINT" | "INT" | "BIGINT" => get_mysql(row, index, int_to_json),
+        "TINYINT UNSIGNED" | "SMALLINT UNSIGNED" | "MEDIUMINT UNSIGNED" | "INT UNSIGNED" | "BIGINT UNSIGNED" => {
+            get_mysql(row, index, uint_to_json)
+        }
+        "YEAR" => row.try_get_unchecked::<u16, _>(index).ok().map(|v| uint_to_json(v.into())),
+        "FLOAT" => get_mysql(row, index, |v: f32| float_to_json(v.into())),
+        "DOUBLE" => get_mysql(row, index, float_to_json),
+        "DECIMAL" => get_mysql(row, index, |v: BigDecimal| Value::String(v.to_string())),
+        "DATE" => get_mysql(row, index, |v: NaiveDate| Value::String(v.to_string())),
+        "TIME" => get_mysql(row, index, |v: MySqlTime| Value::String(v.to_string())),
+        "DATETIME" => get_mysql(row, index, |v: NaiveDateTime| Value::String(format_timestamp(v))),
+        // sqlx pins the session time zone to UTC, so TIMESTAMP values arrive in UTC
+        "TIMESTAMP" => get_mysql(row, index, |v: DateTime<Utc>| Value::String(format_timestamp(v.naive_utc()))),
+        "JSON" => get_mysql(row, index, |v: Value| v),
+        "CHAR" | "VARCHAR" | "TINYTEXT" | "TEXT" | "MEDIUMTEXT" | "LONGTEXT" | "ENUM" | "SET" => {
+            row.try_get_unchecked::<String, _>(index).ok().map(Value::String)
+        }
+        "BINARY" | "VARBINARY" | "TINYBLOB" | "BLOB" | "MEDIUMBLOB" | "LONGBLOB" | "GEOMETRY" => row
+            .try_get_unchecked::<Vec<u8>, _>(index)
+            .ok()
+            .map(|v| Value::String(hex_string("0x", &v))),
+        "BIT" => row.try_get_unchecked::<Vec<u8>, _>(index).ok().map(|v| {
+            Value::String(v.iter().map(|b| format!("{:08b}", b)).collect())
+        }),
+        _ => None,
+    }
+}
+
+/// Renders one result cell using the column's MySQL/MariaDB type.
+pub fn mysql_value_to_json(row: &MySqlRow, index: usize) -> Value {
+    let raw = match row.try_get_raw(index) {
+        Ok(raw) => raw,
+        Err(_) => return Value::Null,
+    };
+    if raw.is_null() {
+        return Value::Null;
+    }
+    let type_name = raw.type_info().name().to_string();
+
+    if let Some(value) = decode_mysql(row, index, &type_name) {
+        return value;
+    }
+
+    match row.try_get_unchecked::<Vec<u8>, _>(index) {
+        // Zero dates ('0000-00-00') come through the binary protocol as empty values
+        Ok(bytes) if bytes.is_empty() => match type_name.as_str() {
+            "DATE" => Value::String("0000-00-00".to_string()),
+            "DATETIME" | "TIMESTAMP" => Value::String("0000-00-00 00:00:00".to_string()),
+            _ => Value::String(String::new()),
+        },
+        Ok(bytes) => match String::from_utf8(bytes) {
+            Ok(text) => Value::String(text),
+            Err(e) => Value::String(hex_string("0x", e.as_bytes())),
+        },
+        Err(_) => Value::Null,
+    }
+}
+
+pub fn mysql_result_columns(columns: &[MySqlColumn], nullable: &[Option<bool>]) -> Vec<ResultColumn> {
+    columns
+        .iter()
+        .enumerate()
+        .map(|(i, c)| ResultColumn {
+            name: c.name().to_string(),
+            db_type: c.type_info().name().to_string(),
+            nullable: nullable.get(i).copied().flatten(),
+        })
+        .collect()
+}
@@ -0,0 +1,10 @@
+use sha2::{Digest, Sha256};
+
+/// Lowercase hex SHA-256 of `text`. Stable across builds and platforms, so it can name
+/// things on disk, and doesn't give away what was hashed (passwords in connection strings).
+pub fn sha256_hex(text: &str) -> String {
+    Sha256::digest(text.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
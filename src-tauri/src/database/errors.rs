@@ -0,0 +1,47 @@
+use sqlx::mysql::MySqlDatabaseError;
+use sqlx::postgres::{PgDatabaseError, PgErrorPosition};
+
+/// A failed statement, with whatever the server said about it beyond the message.
+pub struct DbError {
+    pub message: String,
+    pub code: Option<String>,
+    pub error_number: Option<u16>,
+    pub detail: Option<String>,
+    pub hint: Option<String>,
+    /// 1-based character position in the statement text, when the server reports one
+    pub position: Option<usize>,
+}
+
+impl DbError {
+    pub fn new(message: String) -> Self {
+        DbError {
+            message,
+            code: None,
+            error_number: None,
+            detail: None,
+            hint: None,
+            position: None,
+        }
+    }
+
+    pub fn from_sqlx(context: &str, e: &sqlx::Error) -> Self {
+        let mut error = DbError::new(format!("{}: {}", context, e));
+        let Some(db_error) = e.as_database_error() else {
+            return error;
+        };
+
+        error.code = db_error.code().map(|c| c.to_string());
+        if let Some(pg) = db_error.try_downcast_ref::<PgDatabaseError>() {
+            error.detail = pg.detail().map(String::from);
+            error.hint = pg.hint().map(String::from);
+            // Internal positions point into a query Postgres generated, not ours
+            if let Some(PgErrorPosition::Original(position)) = pg.position() {
+                error.position = Some(position);
+            }
+        }
+        if let Some(mysql) = db_error.try_downcast_ref::<MySqlDatabaseError>() {
+            error.error_number = Some(mysql.number());
+        }
+        error
+    }
+}
@@ -0,0 +1,547 @@
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Instant;
+
+use futures_util::TryStreamExt;
+use sqlparser::ast::Statement;
+use sqlparser::dialect::{Dialect, MySqlDialect, PostgreSqlDialect};
+use sqlparser::tokenizer::Location;
+use sqlx::{Either, Executor};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::types::{QueryParameter, QueryPlan, QueryResponse, QueryResult, ResultPage, StatementError};
+
+use super::connection::{
+    open_mysql_statement, open_postgres_statement, split_mysql_script, split_postgres_script, OpenStatement,
+    RowStream,
+};
+use super::errors::DbError;
+use super::explain::{explain_mysql, explain_postgres};
+use super::params::{bind_placeholders, find_parameters};
+use super::registry::{normalize_db_type, DbConnection, QueryTracker, Session};
+use super::script::{parse_error_location, statement_spans, ScriptMap};
+
+/// Rows returned per statement when the caller doesn't ask for a page size.
+pub const DEFAULT_PAGE_SIZE: usize = 1000;
+
+pub struct PageRequest {
+    pub n: usize,
+    pub reply: oneshot::Sender<Result<ResultPage, String>>,
+}
+
+/// A result set that still has rows on the server, served by the task that holds the
+/// session's pinned connection.
+pub struct CursorHandle {
+    pub session_id: String,
+    pub requests: mpsc::Sender<PageRequest>,
+}
+
+/// One statement of a script, with its placeholders rewritten for the driver.
+struct ScriptStatement {
+    index: usize,
+    sql: String,
+    params: Vec<serde_json::Value>,
+    transaction_effect: Option<bool>,
+    /// Character range of the statement in the script
+    span: Option<(usize, usize)>,
+    /// Where `sql` starts in the script, when it's the user's text as written. Statements
+    /// with placeholders run re-rendered from the AST, so server positions don't map back.
+    source_offset: Option<usize>,
+}
+
+struct Script {
+    statements: Vec<ScriptStatement>,
+    map: ScriptMap,
+}
+
+/// Reads a statement's rows a page at a time, looking one row ahead so it can tell
+/// whether anything is left.
+struct RowReader<'c> {
+    rows: RowStream<'c>,
+    pending: Option<serde_json::Value>,
+    rows_affected: Option<u64>,
+}
+
+impl<'c> RowReader<'c> {
+    fn new(rows: RowStream<'c>) -> Self {
+        RowReader {
+            rows,
+            pending: None,
+            rows_affected: None,
+        }
+    }
+
+    async fn next_row(&mut self) -> Result<Option<serde_json::Value>, sqlx::Error> {
+        while let Some(item) = self.rows.try_next().await? {
+            match item {
+                Either::Left(affected) => *self.rows_affected.get_or_insert(0) += affected,
+                Either::Right(row) => return Ok(Some(row)),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Appends up to `n` rows to `page`. Rows read before an error are kept.
+    async fn next_page(&mut self, n: usize, page: &mut Vec<serde_json::Value>) -> Result<(), sqlx::Error> {
+        let n = n.max(1);
+        if let Some(row) = self.pending.take() {
+            page.push(row);
+        }
+        while page.len() < n {
+            match self.next_row().await? {
+                Some(row) => page.push(row),
+                None => return Ok(()),
+            }
+        }
+        self.pending = self.next_row().await?;
+        Ok(())
+    }
+
+    fn has_more(&self) -> bool {
+        self.pending.is_some()
+    }
+}
+
+fn script_dialect(session: &Session) -> Box<dyn Dialect> {
+    match session.db_type {
+        "postgres" => Box::new(PostgreSqlDialect {}),
+        _ => Box::new(MySqlDialect {}),
+    }
+}
+
+fn split_script(session: &Session, query: &str) -> Result<Vec<Statement>, String> {
+    match session.db_type {
+        "postgres" => split_postgres_script(query),
+        _ => split_mysql_script(query),
+    }
+}
+
+/// How a statement changes the connection's transaction state, if it does.
+fn transaction_effect(statement: &Statement, db_type: &str) -> Option<bool> {
+    match statement {
+        Statement::StartTransaction { .. } => Some(true),
+        Statement::Commit { chain } => Some(*chain),
+        Statement::Rollback { chain, savepoint: None } => Some(*chain),
+        _ if db_type == "mysql" && mysql_commits_implicitly(statement) => Some(false),
+        _ => None,
+    }
+}
+
+/// Statements MySQL runs only after committing the open transaction: DDL, and account,
+/// lock and table maintenance statements.
+fn mysql_commits_implicitly(statement: &Statement) -> bool {
+    match statement {
+        // Temporary tables are the exception
+        Statement::CreateTable(create) => !create.temporary,
+        Statement::Drop { temporary, .. } => !temporary,
+        Statement::AlterTable { .. }
+        | Statement::CreateIndex(_)
+        | Statement::CreateView { .. }
+        | Statement::AlterView { .. }
+        | Statement::CreateDatabase { .. }
+        | Statement::CreateSchema { .. }
+        | Statement::CreateFunction(_)
+        | Statement::CreateProcedure { .. }
+        | Statement::CreateTrigger { .. }
+        | Statement::DropFunction { .. }
+        | Statement::DropProcedure { .. }
+        | Statement::DropTrigger { .. }
+        | Statement::Truncate { .. }
+        | Statement::CreateRole { .. }
+        | Statement::AlterRole { .. }
+        | Statement::Grant { .. }
+        | Statement::Revoke { .. }
+        | Statement::LockTables { .. }
+        | Statement::Analyze { .. }
+        | Statement::OptimizeTable { .. } => true,
+        _ => false,
+    }
+}
+
+/// Placeholders in a script, for prompting before it's executed.
+pub fn script_parameters(db_type: &str, query: &str) -> Result<Vec<QueryParameter>, String> {
+    let statements = match normalize_db_type(db_type)? {
+        "postgres" => split_postgres_script(query)?,
+        _ => split_mysql_script(query)?,
+    };
+    Ok(find_parameters(&statements))
+}
+
+fn prepare_script(
+    session: &Session,
+    statements: Vec<Statement>,
+    map: &ScriptMap,
+    parameters: &HashMap<String, serde_json::Value>,
+) -> Result<Vec<ScriptStatement>, String> {
+    // Ranges only line up with the parsed statements if both split the script the same way
+    let spans = statement_spans(script_dialect(session).as_ref(), map).filter(|spans| spans.len() == statements.len());
+
+    let mut positional = 0;
+    statements
+        .into_iter()
+        .enumerate()
+        .map(|(index, mut stmt)| {
+            let params = bind_placeholders(&mut stmt, session.db_type == "postgres", parameters, &mut positional)?;
+            let span = spans.as_ref().map(|spans| spans[index]);
+            let (sql, source_offset) = match span {
+                Some((start, end)) if params.is_empty() => (map.slice(start, end), Some(start)),
+                _ => (stmt.to_string(), None),
+            };
+            Ok(ScriptStatement {
+                index,
+                sql,
+                params,
+                transaction_effect: transaction_effect(&stmt, session.db_type),
+                span,
+                source_offset,
+            })
+        })
+        .collect()
+}
+
+fn statement_error(error: DbError, statement: &ScriptStatement, map: &ScriptMap) -> StatementError {
+    StatementError {
+        message: error.message,
+        statement_index: statement.index,
+        start: statement.span.map(|(start, _)| map.location(start)),
+        end: statement.span.map(|(_, end)| map.location(end)),
+        code: error.code,
+        error_number: error.error_number,
+        detail: error.detail,
+        hint: error.hint,
+        position: match (error.position, statement.source_offset) {
+            (Some(position), Some(offset)) => Some(map.location(offset + position.saturating_sub(1))),
+            _ => None,
+        },
+    }
+}
+
+/// A script that didn't parse becomes a single failed result, placed on the statement
+/// the parser stopped in.
+fn parse_failure(session: &Session, query_id: String, map: &ScriptMap, message: String) -> QueryResponse {
+    let position = parse_error_location(&message);
+    let spans = statement_spans(script_dialect(session).as_ref(), map).unwrap_or_default();
+    let offset = position
+        .as_ref()
+        .and_then(|p| map.offset(Location::new(p.line as u64, p.column as u64)));
+    let index = offset
+        .and_then(|offset| spans.iter().position(|(_, end)| offset <= *end))
+        .unwrap_or(spans.len().saturating_sub(1));
+    let span = spans.get(index).copied();
+
+    let result = QueryResult {
+        statement: span.map(|(start, end)| map.slice(start, end)).unwrap_or_default(),
+        columns: Vec::new(),
+        column_types: Vec::new(),
+        rows: Vec::new(),
+        row_count: 0,
+        rows_affected: None,
+        execution_time: 0,
+        error: Some(message.clone()),
+        error_details: Some(StatementError {
+            message,
+            statement_index: index,
+            start: span.map(|(start, _)| map.location(start)),
+            end: span.map(|(_, end)| map.location(end)),
+            code: None,
+            error_number: None,
+            detail: None,
+            hint: None,
+            position,
+        }),
+        truncated: false,
+        has_more: false,
+    };
+
+    QueryResponse {
+        query_id,
+        results: vec![result],
+        execution_time: 0,
+        in_transaction: session.in_transaction(),
+        history_error: None,
+    }
+}
+
+async fn open_statement<'c>(
+    conn: &'c mut DbConnection,
+    statement: &'c ScriptStatement,
+) -> Result<OpenStatement<'c>, DbError> {
+    match conn {
+        DbConnection::Postgres(conn) => open_postgres_statement(conn, &statement.sql, &statement.params).await,
+        DbConnection::MySql(conn) => open_mysql_statement(conn, &statement.sql, &statement.params).await,
+    }
+}
+
+/// Runs one statement and reads its first page. The reader is returned only while rows
+/// are still waiting on the server.
+async fn first_page<'c>(
+    conn: &'c mut DbConnection,
+    statement: &'c ScriptStatement,
+    map: &ScriptMap,
+    page_size: usize,
+) -> (QueryResult, Option<RowReader<'c>>) {
+    let start_time = Instant::now();
+    let mut result = QueryResult {
+        statement: statement.sql.clone(),
+        columns: Vec::new(),
+        column_types: Vec::new(),
+        rows: Vec::new(),
+        row_count: 0,
+        rows_affected: None,
+        execution_time: 0,
+        error: None,
+        error_details: None,
+        truncated: false,
+        has_more: false,
+    };
+    let mut failure = None;
+
+    let mut reader = match open_statement(conn, statement).await {
+        Ok(open) => {
+            result.columns = open.columns;
+            result.column_types = open.column_types;
+            Some(RowReader::new(open.rows))
+        }
+        Err(e) => {
+            failure = Some(e);
+            None
+        }
+    };
+
+    if let Some(rows) = reader.as_mut() {
+        if let Err(e) = rows.next_page(page_size, &mut result.rows).await {
+            failure = Some(DbError::from_sqlx("Query failed", &e));
+        }
+        result.rows_affected = rows.rows_affected;
+        result.truncated = failure.is_none() && rows.has_more();
+    }
+
+    if let Some(failure) = failure {
+        result.error = Some(failure.message.clone());
+        result.error_details = Some(statement_error(failure, statement, map));
+    }
+
+    result.row_count = result.rows.len();
+    result.execution_time = start_time.elapsed().as_millis() as u64;
+    let truncated = result.truncated;
+    (result, reader.filter(|_| truncated))
+}
+
+/// Executes a script on the session's pinned connection. Every statement returns at most
+/// `page_size` rows; if the last one has more, its result set stays open for fetch_more
+/// until it is read to the end, closed, or the session runs something else.
+pub async fn execute_script<F>(
+    queries: Arc<QueryTracker>,
+    session: Arc<Session>,
+    query: &str,
+    parameters: &HashMap<String, serde_json::Value>,
+    query_id: Option<String>,
+    page_size: usize,
+    on_start: F,
+) -> Result<QueryResponse, String>
+where
+    F: FnOnce(&str) + Send + 'static,
+{
+    let map = ScriptMap::new(query);
+    let statements = match split_script(&session, query) {
+        Ok(statements) => statements,
+        Err(message) => return Ok(parse_failure(&session, queries.query_id(query_id), &map, message)),
+    };
+    let script = Script {
+        statements: prepare_script(&session, statements, &map, parameters)?,
+        map,
+    };
+
+    let (response_tx, response_rx) = oneshot::channel();
+    tokio::spawn(run_script(queries, session, script, query_id, page_size, on_start, response_tx));
+    response_rx
+        .await
+        .map_err(|_| "Query was aborted".to_string())
+}
+
+async fn run_script<F>(
+    queries: Arc<QueryTracker>,
+    session: Arc<Session>,
+    script: Script,
+    query_id: Option<String>,
+    page_size: usize,
+    on_start: F,
+    response_tx: oneshot::Sender<QueryResponse>,
+) where
+    F: FnOnce(&str) + Send + 'static,
+{
+    // A new run supersedes any result set still open on this session
+    let mut conn = queries.acquire_connection(&session).await;
+
+    // Announce the id before running so the UI can cancel while we're still waiting
+    let query_id = queries.begin_query(query_id, &session).await;
+    on_start(&query_id);
+
+    let start_time = Instant::now();
+    let mut results = Vec::new();
+    let mut cursor = None;
+
+    if let Some((last, rest)) = script.statements.split_last() {
+        let mut failed = false;
+        for statement in rest {
+            // Anything past the first page of an earlier statement is skipped
+            let (result, _) = first_page(&mut conn, statement, &script.map, page_size).await;
+            failed = result.error.is_some();
+            if !failed {
+                track_transaction(&session, statement.transaction_effect);
+            }
+            results.push(result);
+            if failed {
+                break;
+            }
+        }
+
+        if !failed {
+            let (mut result, reader) = first_page(&mut conn, last, &script.map, page_size).await;
+            if result.error.is_none() {
+                track_transaction(&session, last.transaction_effect);
+            }
+            if let Some(reader) = reader {
+                let (requests, pages) = mpsc::channel(1);
+                let handle = CursorHandle {
+                    session_id: session.id.clone(),
+                    requests,
+                };
+                if queries.open_cursor(&query_id, &session, handle).await {
+                    result.has_more = true;
+                    cursor = Some((reader, pages));
+                }
+            }
+            results.push(result);
+        }
+    }
+
+    let response = QueryResponse {
+        query_id: query_id.clone(),
+        results,
+        execution_time: start_time.elapsed().as_millis() as u64,
+        in_transaction: session.in_transaction(),
+        history_error: None,
+    };
+    let delivered = response_tx.send(response).is_ok();
+
+    if let Some((mut reader, mut pages)) = cursor.filter(|_| delivered) {
+        while let Some(request) = pages.recv().await {
+            let mut rows = Vec::new();
+            let page = reader.next_page(request.n, &mut rows).await;
+            let has_more = page.is_ok() && reader.has_more();
+            let _ = request.reply.send(
+                page.map(|_| ResultPage {
+                    query_id: query_id.clone(),
+                    row_count: rows.len(),
+                    rows,
+                    has_more,
+                })
+                .map_err(|e| format!("Failed to fetch rows: {}", e)),
+            );
+            if !has_more {
+                break;
+            }
+        }
+    }
+
+    queries.close_cursor(&query_id).await;
+    queries.finish_query(&query_id).await;
+}
+
+fn track_transaction(session: &Session, effect: Option<bool>) {
+    if let Some(in_transaction) = effect {
+        session.in_transaction.store(in_transaction, Ordering::SeqCst);
+    }
+}
+
+/// Runs a transaction control statement (BEGIN / COMMIT / ROLLBACK) on the session's
+/// pinned connection and records the resulting state. Opening a transaction while one is
+/// already open is refused rather than letting the server commit or ignore it.
+pub async fn run_transaction_statement(
+    queries: &QueryTracker,
+    session: &Session,
+    sql: &str,
+    in_transaction: bool,
+) -> Result<(), String> {
+    let mut conn = queries.acquire_connection(session).await;
+    // Checked under the connection lock so two BEGINs can't both pass
+    if in_transaction && session.in_transaction() {
+        return Err("A transaction is already open on this connection".to_string());
+    }
+    conn_execute(&mut conn, sql).await?;
+    session.in_transaction.store(in_transaction, Ordering::SeqCst);
+    Ok(())
+}
+
+/// Explains a single statement on the session's pinned connection. ANALYZE really runs the
+/// statement, so anything but a query needs `allow_writes`, and its effects are undone
+/// afterwards: rolled back, or inside the user's own transaction rolled back to a savepoint.
+/// What MySQL commits implicitly (DDL) or can't roll back (MyISAM) stays done.
+pub async fn explain_statement(
+    queries: Arc<QueryTracker>,
+    session: Arc<Session>,
+    query: &str,
+    analyze: bool,
+    allow_writes: bool,
+    query_id: Option<String>,
+) -> Result<QueryPlan, String> {
+    let statements = split_script(&session, query)?;
+    let [statement] = statements.as_slice() else {
+        return Err("EXPLAIN needs exactly one statement".to_string());
+    };
+    if analyze && !allow_writes && !matches!(statement, Statement::Query(_)) {
+        return Err("EXPLAIN ANALYZE would run this statement; only queries are analyzed unless writes are allowed"
+            .to_string());
+    }
+    let sql = statement.to_string();
+
+    // Runs on its own task so a caller that goes away can't leave the statement's effects
+    // behind by stopping between BEGIN and ROLLBACK
+    let (plan_tx, plan_rx) = oneshot::channel();
+    tokio::spawn(async move {
+        let plan = run_explain(&queries, &session, &sql, analyze, query_id).await;
+        let _ = plan_tx.send(plan);
+    });
+    plan_rx.await.map_err(|_| "Query was aborted".to_string())?
+}
+
+async fn run_explain(
+    queries: &QueryTracker,
+    session: &Arc<Session>,
+    sql: &str,
+    analyze: bool,
+    query_id: Option<String>,
+) -> Result<QueryPlan, String> {
+    let mut conn = queries.acquire_connection(session).await;
+    let savepoint = session.in_transaction();
+    if analyze {
+        let open = if savepoint { "SAVEPOINT explain_analyze" } else { "BEGIN" };
+        conn_execute(&mut conn, open).await?;
+    }
+
+    let query_id = queries.begin_query(query_id, session).await;
+    let plan = match &mut *conn {
+        DbConnection::Postgres(conn) => explain_postgres(conn, sql, analyze).await,
+        DbConnection::MySql(conn) => explain_mysql(conn, sql, analyze).await,
+    };
+    // A cancel from here on would land on the rollback
+    queries.finish_query(&query_id).await;
+
+    if analyze && savepoint {
+        conn_execute(&mut conn, "ROLLBACK TO SAVEPOINT explain_analyze").await?;
+        conn_execute(&mut conn, "RELEASE SAVEPOINT explain_analyze").await?;
+    } else if analyze {
+        conn_execute(&mut conn, "ROLLBACK").await?;
+    }
+    plan
+}
+
+async fn conn_execute(conn: &mut DbConnection, sql: &str) -> Result<(), String> {
+    let result = match conn {
+        DbConnection::Postgres(conn) => conn.execute(sql).await.map(|_| ()),
+        DbConnection::MySql(conn) => conn.execute(sql).await.map(|_| ()),
+    };
+    result.map_err(|e| format!("{} failed: {}", sql, e))
+}
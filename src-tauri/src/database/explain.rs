@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+use sqlx::mysql::MySqlConnection;
+use sqlx::postgres::PgConnection;
+use sqlx::{Executor, Row};
+
+use crate::types::{PlanNode, QueryPlan};
+
+// Postgres plan keys that are lifted into PlanNode fields instead of `details`
+const POSTGRES_PLAN_FIELDS: &[&str] = &[
+    "Node Type",
+    "Relation Name",
+    "Startup Cost",
+    "Total Cost",
+    "Plan Rows",
+    "Actual Startup Time",
+    "Actual Total Time",
+    "Actual Rows",
+    "Actual Loops",
+    "Plans",
+];
+
+// MySQL/MariaDB objects that describe the parent node rather than a step of the plan
+const MYSQL_METADATA_KEYS: &[&str] = &["cost_info", "r_engine_stats", "r_timing"];
+
+fn number(value: Option<&Value>) -> Option<f64> {
+    match value? {
+        Value::Number(n) => n.as_f64(),
+        // MySQL reports costs as strings ("query_cost": "1.20")
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn empty_node(operation: String) -> PlanNode {
+    PlanNode {
+        operation,
+        relation: None,
+        startup_cost: None,
+        cost: None,
+        rows: None,
+        actual_rows: None,
+        actual_time: None,
+        loops: None,
+        details: HashMap::new(),
+        children: Vec::new(),
+    }
+}
+
+fn postgres_node(plan: &Value) -> PlanNode {
+    let mut node = empty_node(
+        plan.get("Node Type")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Unknown")
+            .to_string(),
+    );
+    node.relation = plan.get("Relation Name").and_then(|v| v.as_str()).map(String::from);
+    node.startup_cost = number(plan.get("Startup Cost"));
+    node.cost = number(plan.get("Total Cost"));
+    node.rows = number(plan.get("Plan Rows"));
+    node.actual_rows = number(plan.get("Actual Rows"));
+    node.actual_time = number(plan.get("Actual Total Time"));
+    node.loops = number(plan.get("Actual Loops"));
+
+    if let Some(fields) = plan.as_object() {
+        for (key, value) in fields {
+            if !POSTGRES_PLAN_FIELDS.contains(&key.as_str()) {
+                node.details.insert(key.clone(), value.clone());
+            }
+        }
+    }
+
+    if let Some(children) = plan.get("Plans").and_then(|v| v.as_array()) {
+        node.children = children.iter().map(postgres_node).collect();
+    }
+    node
+}
+
+/// Normalises the output of `EXPLAIN (FORMAT JSON)`, which is a one-element array
+/// holding the plan and the planning/execution timings.
+pub fn normalize_postgres_plan(raw: Value, analyzed: bool) -> Result<QueryPlan, String> {
+    let top = raw
+        .get(0)
+        .ok_or_else(|| "EXPLAIN returned an empty plan".to_string())?;
+    let plan = top
+        .get("Plan")
+        .ok_or_else(|| "EXPLAIN output has no Plan".to_string())?;
+
+    Ok(QueryPlan {
+        db_type: "postgres".to_string(),
+        analyzed,
+        root: postgres_node(plan),
+        planning_time: number(top.get("Planning Time")),
+        execution_time: number(top.get("Execution Time")),
+        raw,
+    })
+}
+
+fn mysql_operation(table: &Value) -> String {
+    let access_type = table.get("access_type").and_then(|v| v.as_str()).unwrap_or("");
+    match access_type {
+        "ALL" => "Full Table Scan".to_string(),
+        "index" => "Full Index Scan".to_string(),
+        "range" => "Index Range Scan".to_string(),
+        "ref" | "eq_ref" | "ref_or_null" => "Index Lookup".to_string(),
+        "const" | "system" => "Constant Lookup".to_string(),
+        "" => "Table".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Builds a node from one object of MySQL/MariaDB's JSON plan. Nested objects and arrays
+/// of objects become children; scalar fields are kept as details.
+fn mysql_node(operation: String, value: &Value) -> PlanNode {
+    let mut node = empty_node(operation);
+    let Some(fields) = value.as_object() else {
+        return node;
+    };
+
+    let cost_info = fields.get("cost_info");
+    node.cost = number(fields.get("cost"))
+        .or_else(|| cost_info.and_then(|c| number(c.get("query_cost"))))
+        .or_else(|| cost_info.and_then(|c| number(c.get("prefix_cost"))));
+    node.rows = number(fields.get("rows")).or_else(|| number(fields.get("rows_examined_per_scan")));
+    node.actual_rows = number(fields.get("r_rows"));
+    node.actual_time = number(fields.get("r_total_time_ms"));
+    node.loops = number(fields.get("r_loops"));
+
+    for (key, child) in fields {
+        if MYSQL_METADATA_KEYS.contains(&key.as_str()) {
+            continue;
+        }
+        match child {
+            Value::Object(_) => node.children.push(mysql_child(key, child)),
+            Value::Array(items) if items.iter().all(|item| item.is_object()) => {
+                for item in items {
+                    // nested_loop and friends wrap each step as {"table": {...}}
+                    match item.as_object() {
+                        Some(wrapper) if wrapper.len() == 1 => {
+                            let (inner_key, inner) = wrapper.iter().next().unwrap();
+                            node.children.push(mysql_child(inner_key, inner));
+                        }
+                        _ => node.children.push(mysql_child(key, item)),
+                    }
+                }
+            }
+            _ => {
+                node.details.insert(key.clone(), child.clone());
+            }
+        }
+    }
+    node
+}
+
+fn mysql_child(key: &str, value: &Value) -> PlanNode {
+    if key == "table" {
+        let mut node = mysql_node(mysql_operation(value), value);
+        node.relation = value.get("table_name").and_then(|v| v.as_str()).map(String::from);
+        node
+    } else {
+        mysql_node(key.replace('_', " "), value)
+    }
+}
+
+/// Normalises `EXPLAIN FORMAT=JSON` / `ANALYZE FORMAT=JSON` output, rooted at `query_block`.
+pub fn normalize_mysql_plan(raw: Value, analyzed: bool) -> Result<QueryPlan, String> {
+    let block = raw
+        .get("query_block")
+        .ok_or_else(|| "EXPLAIN output has no query_block".to_string())?;
+
+    let mut root = mysql_node("query block".to_string(), block);
+    if let Some(id) = block.get("select_id") {
+        root.operation = format!("query block #{}", id);
+    }
+    let execution_time = root.actual_time;
+
+    Ok(QueryPlan {
+        db_type: "mysql".to_string(),
+        analyzed,
+        root,
+        planning_time: None,
+        execution_time,
+        raw,
+    })
+}
+
+pub async fn explain_postgres(conn: &mut PgConnection, sql: &str, analyze: bool) -> Result<QueryPlan, String> {
+    let explain = if analyze {
+        format!("EXPLAIN (ANALYZE, BUFFERS, FORMAT JSON) {}", sql)
+    } else {
+        format!("EXPLAIN (FORMAT JSON) {}", sql)
+    };
+
+    let raw: Value = conn
+        .fetch_one(explain.as_str())
+        .await
+        .and_then(|row| row.try_get(0))
+        .map_err(|e| format!("Failed to explain query: {}", e))?;
+
+    normalize_postgres_plan(raw, analyze)
+}
+
+pub async fn explain_mysql(conn: &mut MySqlConnection, sql: &str, analyze: bool) -> Result<QueryPlan, String> {
+    if analyze {
+        // MySQL only has EXPLAIN ANALYZE in tree format; ANALYZE FORMAT=JSON is MariaDB's
+        let version: String = conn
+            .fetch_one("SELECT VERSION()")
+            .await
+            .and_then(|row| row.try_get(0))
+            .map_err(|e| format!("Failed to read server version: {}", e))?;
+        if !version.to_lowercase().contains("mariadb") {
+            return Err("ANALYZE FORMAT=JSON is only supported on MariaDB".to_string());
+        }
+    }
+
+    let explain = if analyze {
+        format!("ANALYZE FORMAT=JSON {}", sql)
+    } else {
+        format!("EXPLAIN FORMAT=JSON {}", sql)
+    };
+
+    let text: String = conn
+        .fetch_one(explain.as_str())
+        .await
+        .and_then(|row| row.try_get(0))
+        .map_err(|e| format!("Failed to explain query: {}", e))?;
+    let raw: Value = serde_json::from_str(&text).map_err(|e| format!("Failed to parse plan: {}", e))?;
+
+    normalize_mysql_plan(raw, analyze)
+}
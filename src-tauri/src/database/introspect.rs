@@ -0,0 +1,1894 @@
+use std::collections::HashMap;
+
+use futures_util::StreamExt;
+use sqlx::mysql::MySqlPool;
+use sqlx::postgres::PgPool;
+use sqlx::{Executor, Row};
+
+use crate::types::{
+    CheckConstraint, Column, ColumnInfo, DatabaseEntry, DatabaseInfo, DomainType, EnumType, ForeignKey, IndexInfo,
+    RoutineInfo, RoutineKind, Schema, SchemaEntry, SequenceInfo, SequenceOwner, Table, TableEntry, TableInfo,
+    TableKind, TableRef, TableStats, TriggerInfo, UniqueConstraint,
+};
+
+use super::registry::{DbPool, Session};
+
+/// How many databases are introspected at once when a connection lists the whole server.
+const MAX_CONCURRENT_DATABASES: usize = 4;
+
+/// Which Postgres schemas introspection covers. `pg_*` schemas and `information_schema`
+/// are always left out.
+#[derive(Debug, Clone, Default)]
+pub struct SchemaFilter {
+    pub include: Option<Vec<String>>,
+    pub exclude: Vec<String>,
+    // Narrows introspection down to the tables of this name, e.g. to describe one table
+    pub table: Option<String>,
+}
+
+impl SchemaFilter {
+    pub fn new(include: Option<&Vec<String>>, exclude: Option<&Vec<String>>) -> Self {
+        SchemaFilter {
+            include: include.filter(|schemas| !schemas.is_empty()).cloned(),
+            exclude: exclude.cloned().unwrap_or_default(),
+            table: None,
+        }
+    }
+}
+
+/// WHERE condition restricting `column` to the schemas a filter allows. Expects the
+/// include list bound as `$1` and the exclude list as `$2`.
+pub(super) fn postgres_schema_condition(column: &str) -> String {
+    format!(
+        r#"{col}::text <> 'information_schema'
+            AND {col}::text NOT LIKE 'pg\_%'
+            AND ($1::text[] IS NULL OR {col}::text = ANY($1))
+            AND NOT ({col}::text = ANY($2))"#,
+        col = column
+    )
+}
+
+/// The schema condition, also narrowed to the filter's table, which is bound as `$3`.
+fn postgres_table_condition(schema_column: &str, table_column: &str) -> String {
+    format!(
+        "{} AND ($3::text IS NULL OR {}::text = $3)",
+        postgres_schema_condition(schema_column),
+        table_column
+    )
+}
+
+/// Everything read about one database's tables, keyed by (schema, table). Each part comes
+/// from a single query over the whole database and is handed out table by table.
+#[derive(Default)]
+struct Catalog {
+    columns: HashMap<(String, String), Vec<ColumnInfo>>,
+    dependencies: HashMap<(String, String), Vec<TableRef>>,
+    indexes: HashMap<(String, String), Vec<IndexInfo>>,
+    constraints: HashMap<(String, String), TableConstraints>,
+    foreign_keys: ForeignKeys,
+}
+
+impl Catalog {
+    fn table(
+        &mut self,
+        schema: String,
+        name: String,
+        kind: TableKind,
+        definition: Option<String>,
+        comment: Option<String>,
+    ) -> TableInfo {
+        let key = (schema, name);
+        let foreign_keys = self.foreign_keys.remove(&key).unwrap_or_default();
+        let mut columns = self.columns.remove(&key).unwrap_or_default();
+        for column in &mut columns {
+            apply_foreign_key(column, &foreign_keys);
+        }
+        let constraints = self.constraints.remove(&key).unwrap_or_default();
+
+        TableInfo {
+            depends_on: self.dependencies.remove(&key).unwrap_or_default(),
+            indexes: self.indexes.remove(&key).unwrap_or_default(),
+            unique_constraints: constraints.unique,
+            check_constraints: constraints.check,
+            stats: None,
+            name: key.1,
+            schema: Some(key.0),
+            kind,
+            definition,
+            columns,
+            foreign_keys,
+            comment,
+        }
+    }
+}
+
+/// The diagram's view of introspected tables.
+fn schema_from_tables(tables: Vec<TableInfo>) -> Schema {
+    let tables = tables
+        .into_iter()
+        .map(|info| {
+            let mut table = Table {
+                name: info.name,
+                schema: info.schema,
+                kind: info.kind,
+                depends_on: info.depends_on,
+                columns: info
+                    .columns
+                    .into_iter()
+                    .map(|col| Column {
+                        name: col.name,
+                        column_type: col.column_type,
+                        is_primary_key: col.is_primary_key,
+                        is_foreign_key: col.is_foreign_key,
+                        is_unique: false,
+                        foreign_key_target_table: col.foreign_key_target_table,
+                        foreign_key_target_column: col.foreign_key_target_column,
+                        foreign_key_target_schema: col.foreign_key_target_schema,
+                        enum_values: col.enum_values,
+                        comment: col.comment,
+                        is_multivalued: false,
+                        is_derived: false,
+                        cardinality_source: None,
+                        cardinality_target: None,
+                    })
+                    .collect(),
+                foreign_keys: info.foreign_keys,
+                comment: info.comment,
+            };
+            mark_unique_columns(&mut table, &info.indexes);
+            table
+        })
+        .collect();
+    Schema { tables }
+}
+
+/// Unique and check constraints of one table.
+#[derive(Default)]
+struct TableConstraints {
+    unique: Vec<UniqueConstraint>,
+    check: Vec<CheckConstraint>,
+}
+
+/// Marks the columns of single-column unique indexes. Partial indexes don't make a column
+/// unique, and primary keys are already marked as such.
+fn mark_unique_columns(table: &mut Table, indexes: &[IndexInfo]) {
+    for index in indexes {
+        if !index.is_unique || index.is_primary || index.predicate.is_some() || index.columns.len() != 1 {
+            continue;
+        }
+        if let Some(col) = table.columns.iter_mut().find(|c| c.name == index.columns[0]) {
+            col.is_unique = true;
+        }
+    }
+}
+
+/// Columns keyed by (schema, table), in table order. information_schema.columns leaves
+/// materialized views out, so their columns come from pg_attribute; they can't have keys anyway.
+/// Enums and other user-defined types are reported by name rather than as `USER-DEFINED`, and
+/// domain-typed columns by their domain.
+async fn postgres_columns(
+    pool: &PgPool,
+    filter: &SchemaFilter,
+) -> Result<HashMap<(String, String), Vec<ColumnInfo>>, String> {
+    let query = format!(
+        r#"
+        SELECT
+            c.table_schema::text AS table_schema,
+            c.table_name::text AS table_name,
+            c.column_name::text AS column_name,
+            CASE
+                WHEN c.domain_name IS NOT NULL THEN c.domain_name::text
+                WHEN c.data_type = 'USER-DEFINED' THEN c.udt_name::text
+                WHEN c.data_type = 'ARRAY' THEN substr(c.udt_name::text, 2) || '[]'
+                ELSE c.data_type::text
+            END AS data_type,
+            c.is_nullable::text AS is_nullable,
+            c.column_default::text AS column_default,
+            c.ordinal_position::int AS position,
+            pk.column_name IS NOT NULL AS is_primary_key,
+            ARRAY(
+                SELECT e.enumlabel::text
+                FROM pg_enum e
+                JOIN pg_type t ON t.oid = e.enumtypid
+                JOIN pg_namespace tn ON tn.oid = t.typnamespace
+                WHERE t.typname = c.udt_name::text AND tn.nspname = c.udt_schema::text
+                ORDER BY e.enumsortorder
+            ) AS enum_values,
+            col_description(
+                (quote_ident(c.table_schema::text) || '.' || quote_ident(c.table_name::text))::regclass,
+                c.ordinal_position::int
+            ) AS comment
+        FROM information_schema.columns c
+        LEFT JOIN (
+            SELECT n.nspname::text AS table_schema, t.relname::text AS table_name, a.attname::text AS column_name
+            FROM pg_constraint p
+            JOIN pg_class t ON t.oid = p.conrelid
+            JOIN pg_namespace n ON n.oid = t.relnamespace
+            JOIN pg_attribute a ON a.attrelid = p.conrelid AND a.attnum = ANY(p.conkey)
+            WHERE p.contype = 'p'
+        ) pk ON pk.table_schema = c.table_schema::text
+            AND pk.table_name = c.table_name::text
+            AND pk.column_name = c.column_name::text
+        WHERE {}
+        UNION ALL
+        SELECT
+            n.nspname::text,
+            c.relname::text,
+            a.attname::text,
+            format_type(a.atttypid, a.atttypmod),
+            CASE WHEN a.attnotnull THEN 'NO' ELSE 'YES' END,
+            NULL::text,
+            a.attnum::int,
+            false,
+            ARRAY(
+                SELECT e.enumlabel::text
+                FROM pg_enum e
+                WHERE e.enumtypid = a.atttypid
+                ORDER BY e.enumsortorder
+            ),
+            col_description(a.attrelid, a.attnum)
+        FROM pg_attribute a
+        JOIN pg_class c ON c.oid = a.attrelid
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        WHERE c.relkind = 'm' AND a.attnum > 0 AND NOT a.attisdropped
+            AND {}
+        ORDER BY 1, 2, 7
+        "#,
+        postgres_table_condition("c.table_schema", "c.table_name"),
+        postgres_table_condition("n.nspname", "c.relname")
+    );
+
+    let rows = sqlx::query(&query)
+        .bind(filter.include.clone())
+        .bind(filter.exclude.clone())
+        .bind(filter.table.clone())
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to fetch columns: {}", e))?;
+
+    let mut columns: HashMap<(String, String), Vec<ColumnInfo>> = HashMap::new();
+    for row in rows {
+        columns
+            .entry((row.get("table_schema"), row.get("table_name")))
+            .or_default()
+            .push(ColumnInfo {
+                name: row.get("column_name"),
+                column_type: row.get("data_type"),
+                nullable: row.get::<String, _>("is_nullable") == "YES",
+                is_primary_key: row.get("is_primary_key"),
+                is_foreign_key: false,
+                foreign_key_target_table: None,
+                foreign_key_target_column: None,
+                foreign_key_target_schema: None,
+                enum_values: row.get("enum_values"),
+                default_value: row.get("column_default"),
+                comment: row.get("comment"),
+            });
+    }
+    Ok(columns)
+}
+
+/// Indexes keyed by (schema, table). Unique constraints show up here too, through the
+/// index that backs them.
+async fn postgres_indexes(
+    pool: &PgPool,
+    filter: &SchemaFilter,
+) -> Result<HashMap<(String, String), Vec<IndexInfo>>, String> {
+    let query = format!(
+        r#"
+        SELECT
+            n.nspname AS table_schema,
+            t.relname AS table_name,
+            i.relname AS index_name,
+            ix.indisunique AS is_unique,
+            ix.indisprimary AS is_primary,
+            am.amname::text AS method,
+            pg_get_expr(ix.indpred, ix.indrelid, true) AS predicate,
+            ARRAY(
+                SELECT pg_get_indexdef(ix.indexrelid, k, true)
+                FROM generate_series(1, ix.indnkeyatts) AS k
+                ORDER BY k
+            ) AS columns
+        FROM pg_index ix
+        JOIN pg_class i ON i.oid = ix.indexrelid
+        JOIN pg_class t ON t.oid = ix.indrelid
+        JOIN pg_namespace n ON n.oid = t.relnamespace
+        JOIN pg_am am ON am.oid = i.relam
+        WHERE {}
+        ORDER BY 1, 2, 3
+        "#,
+        postgres_table_condition("n.nspname", "t.relname")
+    );
+
+    let rows = sqlx::query(&query)
+        .bind(filter.include.clone())
+        .bind(filter.exclude.clone())
+        .bind(filter.table.clone())
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to fetch indexes: {}", e))?;
+
+    let mut indexes: HashMap<(String, String), Vec<IndexInfo>> = HashMap::new();
+    for row in rows {
+        indexes
+            .entry((row.get("table_schema"), row.get("table_name")))
+            .or_default()
+            .push(IndexInfo {
+                name: row.get("index_name"),
+                columns: row.get("columns"),
+                is_unique: row.get("is_unique"),
+                is_primary: row.get("is_primary"),
+                method: row.get("method"),
+                predicate: row.get("predicate"),
+            });
+    }
+    Ok(indexes)
+}
+
+/// Unique and check constraints keyed by (schema, table).
+async fn postgres_constraints(
+    pool: &PgPool,
+    filter: &SchemaFilter,
+) -> Result<HashMap<(String, String), TableConstraints>, String> {
+    let query = format!(
+        r#"
+        SELECT
+            n.nspname AS table_schema,
+            t.relname AS table_name,
+            c.conname AS constraint_name,
+            c.contype::text AS constraint_type,
+            pg_get_expr(c.conbin, c.conrelid, true) AS expression,
+            ARRAY(
+                SELECT a.attname::text
+                FROM unnest(c.conkey) WITH ORDINALITY AS k(attnum, ord)
+                JOIN pg_attribute a ON a.attrelid = c.conrelid AND a.attnum = k.attnum
+                ORDER BY k.ord
+            ) AS columns
+        FROM pg_constraint c
+        JOIN pg_class t ON t.oid = c.conrelid
+        JOIN pg_namespace n ON n.oid = t.relnamespace
+        WHERE c.contype IN ('u', 'c')
+            AND {}
+        ORDER BY 1, 2, 3
+        "#,
+        postgres_table_condition("n.nspname", "t.relname")
+    );
+
+    let rows = sqlx::query(&query)
+        .bind(filter.include.clone())
+        .bind(filter.exclude.clone())
+        .bind(filter.table.clone())
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to fetch constraints: {}", e))?;
+
+    let mut constraints: HashMap<(String, String), TableConstraints> = HashMap::new();
+    for row in rows {
+        let entry = constraints
+            .entry((row.get("table_schema"), row.get("table_name")))
+            .or_default();
+        let name: String = row.get("constraint_name");
+        if row.get::<String, _>("constraint_type") == "u" {
+            entry.unique.push(UniqueConstraint {
+                name,
+                columns: row.get("columns"),
+            });
+        } else {
+            entry.check.push(CheckConstraint {
+                name,
+                expression: row.get::<Option<String>, _>("expression").unwrap_or_default(),
+            });
+        }
+    }
+    Ok(constraints)
+}
+
+/// Foreign keys keyed by (schema, table), one entry per constraint.
+type ForeignKeys = HashMap<(String, String), Vec<ForeignKey>>;
+
+/// The foreign key a column is part of and the column it references, if known. A column in
+/// more than one foreign key reports the first by constraint name.
+fn column_reference<'a>(foreign_keys: &'a [ForeignKey], column: &str) -> Option<(&'a ForeignKey, Option<&'a str>)> {
+    let fk = foreign_keys.iter().find(|fk| fk.columns.iter().any(|c| c == column))?;
+    Some((fk, fk.target_column(column)))
+}
+
+async fn postgres_foreign_keys(pool: &PgPool, filter: &SchemaFilter) -> Result<ForeignKeys, String> {
+    // NO ACTION is the default and is left as NULL
+    let query = format!(
+        r#"
+        SELECT
+            n.nspname AS table_schema,
+            t.relname AS table_name,
+            c.conname AS constraint_name,
+            fns.nspname AS foreign_table_schema,
+            ft.relname AS foreign_table_name,
+            ARRAY(
+                SELECT a.attname::text
+                FROM unnest(c.conkey) WITH ORDINALITY AS k(attnum, ord)
+                JOIN pg_attribute a ON a.attrelid = c.conrelid AND a.attnum = k.attnum
+                ORDER BY k.ord
+            ) AS columns,
+            ARRAY(
+                SELECT a.attname::text
+                FROM unnest(c.confkey) WITH ORDINALITY AS k(attnum, ord)
+                JOIN pg_attribute a ON a.attrelid = c.confrelid AND a.attnum = k.attnum
+                ORDER BY k.ord
+            ) AS foreign_columns,
+            CASE c.confdeltype
+                WHEN 'r' THEN 'RESTRICT' WHEN 'c' THEN 'CASCADE'
+                WHEN 'n' THEN 'SET NULL' WHEN 'd' THEN 'SET DEFAULT'
+            END AS on_delete,
+            CASE c.confupdtype
+                WHEN 'r' THEN 'RESTRICT' WHEN 'c' THEN 'CASCADE'
+                WHEN 'n' THEN 'SET NULL' WHEN 'd' THEN 'SET DEFAULT'
+            END AS on_update
+        FROM pg_constraint c
+        JOIN pg_class t ON t.oid = c.conrelid
+        JOIN pg_namespace n ON n.oid = t.relnamespace
+        JOIN pg_class ft ON ft.oid = c.confrelid
+        JOIN pg_namespace fns ON fns.oid = ft.relnamespace
+        WHERE c.contype = 'f'
+            AND {}
+        ORDER BY 1, 2, 3
+        "#,
+        postgres_table_condition("n.nspname", "t.relname")
+    );
+
+    let rows = sqlx::query(&query)
+        .bind(filter.include.clone())
+        .bind(filter.exclude.clone())
+        .bind(filter.table.clone())
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to fetch foreign keys: {}", e))?;
+
+    let mut foreign_keys = ForeignKeys::new();
+    for row in rows {
+        let schema_name: String = row.get("table_schema");
+        let target_schema: String = row.get("foreign_table_schema");
+        let fk = ForeignKey {
+            name: Some(row.get("constraint_name")),
+            columns: row.get("columns"),
+            target_schema: (target_schema != schema_name).then_some(target_schema),
+            target_table: row.get("foreign_table_name"),
+            target_columns: row.get("foreign_columns"),
+            on_delete: row.get("on_delete"),
+            on_update: row.get("on_update"),
+        };
+        foreign_keys
+            .entry((schema_name, row.get("table_name")))
+            .or_default()
+            .push(fk);
+    }
+    Ok(foreign_keys)
+}
+
+/// Sets a column's foreign-key fields from its table's foreign keys.
+fn apply_foreign_key(column: &mut ColumnInfo, foreign_keys: &[ForeignKey]) {
+    if let Some((fk, target_column)) = column_reference(foreign_keys, &column.name) {
+        column.is_foreign_key = true;
+        column.foreign_key_target_table = Some(fk.target_table.clone());
+        column.foreign_key_target_column = target_column.map(str::to_string);
+        column.foreign_key_target_schema = fk.target_schema.clone();
+    }
+}
+
+/// What each view and materialized view reads from, keyed by (schema, view). Taken from the
+/// rewrite rule's entries in pg_depend, so it covers everything the view's query touches.
+async fn postgres_view_dependencies(
+    pool: &PgPool,
+    filter: &SchemaFilter,
+) -> Result<HashMap<(String, String), Vec<TableRef>>, String> {
+    let query = format!(
+        r#"
+        SELECT DISTINCT
+            vn.nspname AS view_schema,
+            v.relname AS view_name,
+            tn.nspname AS table_schema,
+            t.relname AS table_name
+        FROM pg_rewrite r
+        JOIN pg_depend d
+            ON d.classid = 'pg_rewrite'::regclass
+            AND d.objid = r.oid
+            AND d.refclassid = 'pg_class'::regclass
+        JOIN pg_class v ON v.oid = r.ev_class
+        JOIN pg_namespace vn ON vn.oid = v.relnamespace
+        JOIN pg_class t ON t.oid = d.refobjid
+        JOIN pg_namespace tn ON tn.oid = t.relnamespace
+        WHERE v.relkind IN ('v', 'm')
+            AND t.oid <> v.oid
+            AND {}
+        ORDER BY 1, 2, 3, 4
+        "#,
+        postgres_table_condition("vn.nspname", "v.relname")
+    );
+
+    let rows = sqlx::query(&query)
+        .bind(filter.include.clone())
+        .bind(filter.exclude.clone())
+        .bind(filter.table.clone())
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to fetch view dependencies: {}", e))?;
+
+    let mut dependencies: HashMap<(String, String), Vec<TableRef>> = HashMap::new();
+    for row in rows {
+        dependencies
+            .entry((row.get("view_schema"), row.get("view_name")))
+            .or_default()
+            .push(TableRef {
+                schema: Some(row.get("table_schema")),
+                name: row.get("table_name"),
+            });
+    }
+    Ok(dependencies)
+}
+
+/// Enum types with their labels in declaration order.
+async fn postgres_enums(pool: &PgPool, filter: &SchemaFilter) -> Result<Vec<EnumType>, String> {
+    let query = format!(
+        r#"
+        SELECT
+            n.nspname AS type_schema,
+            t.typname AS type_name,
+            ARRAY(
+                SELECT e.enumlabel::text
+                FROM pg_enum e
+                WHERE e.enumtypid = t.oid
+                ORDER BY e.enumsortorder
+            ) AS labels
+        FROM pg_type t
+        JOIN pg_namespace n ON n.oid = t.typnamespace
+        WHERE t.typtype = 'e'
+            AND {}
+        ORDER BY 1, 2
+        "#,
+        postgres_schema_condition("n.nspname")
+    );
+
+    let rows = sqlx::query(&query)
+        .bind(filter.include.clone())
+        .bind(filter.exclude.clone())
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to fetch enum types: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| EnumType {
+            schema: Some(row.get("type_schema")),
+            name: row.get("type_name"),
+            labels: row.get("labels"),
+        })
+        .collect())
+}
+
+/// Domains with their base type and check constraints.
+async fn postgres_domains(pool: &PgPool, filter: &SchemaFilter) -> Result<Vec<DomainType>, String> {
+    let query = format!(
+        r#"
+        SELECT
+            n.nspname AS domain_schema,
+            t.typname AS domain_name,
+            format_type(t.typbasetype, t.typtypmod) AS base_type,
+            t.typnotnull AS not_null,
+            t.typdefault AS default_value,
+            c.conname AS constraint_name,
+            pg_get_expr(c.conbin, 0, true) AS expression
+        FROM pg_type t
+        JOIN pg_namespace n ON n.oid = t.typnamespace
+        LEFT JOIN pg_constraint c ON c.contypid = t.oid AND c.contype = 'c'
+        WHERE t.typtype = 'd'
+            AND {}
+        ORDER BY 1, 2, 6
+        "#,
+        postgres_schema_condition("n.nspname")
+    );
+
+    let rows = sqlx::query(&query)
+        .bind(filter.include.clone())
+        .bind(filter.exclude.clone())
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to fetch domains: {}", e))?;
+
+    // One row per constraint, or a single row for a domain without any
+    let mut domains: Vec<DomainType> = Vec::new();
+    for row in rows {
+        let schema: String = row.get("domain_schema");
+        let name: String = row.get("domain_name");
+        let constraint = row
+            .get::<Option<String>, _>("constraint_name")
+            .map(|constraint_name| CheckConstraint {
+                name: constraint_name,
+                expression: row.get::<Option<String>, _>("expression").unwrap_or_default(),
+            });
+
+        match domains.last_mut() {
+            Some(domain) if domain.name == name && domain.schema.as_deref() == Some(schema.as_str()) => {
+                domain.constraints.extend(constraint);
+            }
+            _ => domains.push(DomainType {
+                schema: Some(schema),
+                name,
+                base_type: row.get("base_type"),
+                not_null: row.get("not_null"),
+                default_value: row.get("default_value"),
+                constraints: constraint.into_iter().collect(),
+            }),
+        }
+    }
+    Ok(domains)
+}
+
+/// Sequences with the column they belong to, if any. Serial and identity columns own
+/// their sequence through an automatic or internal pg_depend entry.
+async fn postgres_sequences(pool: &PgPool, filter: &SchemaFilter) -> Result<Vec<SequenceInfo>, String> {
+    let query = format!(
+        r#"
+        SELECT
+            n.nspname AS sequence_schema,
+            c.relname AS sequence_name,
+            format_type(s.seqtypid, NULL) AS data_type,
+            s.seqstart AS start_value,
+            s.seqincrement AS increment,
+            tn.nspname AS owner_schema,
+            t.relname AS owner_table,
+            a.attname AS owner_column
+        FROM pg_sequence s
+        JOIN pg_class c ON c.oid = s.seqrelid
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        LEFT JOIN pg_depend d
+            ON d.classid = 'pg_class'::regclass
+            AND d.objid = c.oid
+            AND d.refclassid = 'pg_class'::regclass
+            AND d.deptype IN ('a', 'i')
+        LEFT JOIN pg_class t ON t.oid = d.refobjid
+        LEFT JOIN pg_namespace tn ON tn.oid = t.relnamespace
+        LEFT JOIN pg_attribute a ON a.attrelid = d.refobjid AND a.attnum = d.refobjsubid
+        WHERE {}
+        ORDER BY 1, 2
+        "#,
+        postgres_schema_condition("n.nspname")
+    );
+
+    let rows = sqlx::query(&query)
+        .bind(filter.include.clone())
+        .bind(filter.exclude.clone())
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to fetch sequences: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let schema: String = row.get("sequence_schema");
+            let owner_schema: Option<String> = row.get("owner_schema");
+            let owned_by = match (row.get("owner_table"), row.get("owner_column")) {
+                (Some(table), Some(column)) => Some(SequenceOwner {
+                    schema: owner_schema.filter(|s| *s != schema),
+                    table,
+                    column,
+                }),
+                _ => None,
+            };
+            SequenceInfo {
+                schema: Some(schema),
+                name: row.get("sequence_name"),
+                data_type: row.get("data_type"),
+                start_value: row.get("start_value"),
+                increment: row.get("increment"),
+                owned_by,
+            }
+        })
+        .collect())
+}
+
+/// Functions and procedures, leaving out those that belong to an extension.
+async fn postgres_routines(pool: &PgPool, filter: &SchemaFilter) -> Result<Vec<RoutineInfo>, String> {
+    let query = format!(
+        r#"
+        SELECT
+            n.nspname AS routine_schema,
+            p.proname AS routine_name,
+            p.prokind = 'p' AS is_procedure,
+            pg_get_function_arguments(p.oid) AS arguments,
+            CASE WHEN p.prokind <> 'p' THEN pg_get_function_result(p.oid) END AS return_type,
+            l.lanname AS language,
+            CASE WHEN l.lanname NOT IN ('c', 'internal') THEN NULLIF(p.prosrc, '') END AS body
+        FROM pg_proc p
+        JOIN pg_namespace n ON n.oid = p.pronamespace
+        JOIN pg_language l ON l.oid = p.prolang
+        WHERE p.prokind IN ('f', 'p')
+            AND NOT EXISTS (
+                SELECT 1 FROM pg_depend d
+                WHERE d.classid = 'pg_proc'::regclass AND d.objid = p.oid AND d.deptype = 'e'
+            )
+            AND {}
+        ORDER BY 1, 2, 4
+        "#,
+        postgres_schema_condition("n.nspname")
+    );
+
+    let rows = sqlx::query(&query)
+        .bind(filter.include.clone())
+        .bind(filter.exclude.clone())
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to fetch routines: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| RoutineInfo {
+            schema: Some(row.get("routine_schema")),
+            name: row.get("routine_name"),
+            kind: if row.get("is_procedure") { RoutineKind::Procedure } else { RoutineKind::Function },
+            arguments: row.get("arguments"),
+            return_type: row.get("return_type"),
+            language: row.get("language"),
+            body: row.get("body"),
+        })
+        .collect())
+}
+
+/// Splits pg_trigger.tgtype into timing, events and level.
+fn postgres_trigger_type(tgtype: i16) -> (String, Vec<String>, String) {
+    let timing = if tgtype & 64 != 0 {
+        "INSTEAD OF"
+    } else if tgtype & 2 != 0 {
+        "BEFORE"
+    } else {
+        "AFTER"
+    };
+    let events = [(4, "INSERT"), (16, "UPDATE"), (8, "DELETE"), (32, "TRUNCATE")]
+        .iter()
+        .filter(|(bit, _)| tgtype & bit != 0)
+        .map(|(_, event)| event.to_string())
+        .collect();
+    let level = if tgtype & 1 != 0 { "ROW" } else { "STATEMENT" };
+    (timing.to_string(), events, level.to_string())
+}
+
+/// User-defined triggers; the ones Postgres creates to enforce foreign keys are left out.
+async fn postgres_triggers(pool: &PgPool, filter: &SchemaFilter) -> Result<Vec<TriggerInfo>, String> {
+    let query = format!(
+        r#"
+        SELECT
+            n.nspname AS table_schema,
+            c.relname AS table_name,
+            t.tgname AS trigger_name,
+            t.tgtype AS trigger_type,
+            t.tgfoid::regproc::text AS function,
+            t.tgenabled <> 'D' AS enabled
+        FROM pg_trigger t
+        JOIN pg_class c ON c.oid = t.tgrelid
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        WHERE NOT t.tgisinternal
+            AND {}
+        ORDER BY 1, 2, 3
+        "#,
+        postgres_schema_condition("n.nspname")
+    );
+
+    let rows = sqlx::query(&query)
+        .bind(filter.include.clone())
+        .bind(filter.exclude.clone())
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to fetch triggers: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let (timing, events, level) = postgres_trigger_type(row.get("trigger_type"));
+            TriggerInfo {
+                schema: Some(row.get("table_schema")),
+                name: row.get("trigger_name"),
+                table: row.get("table_name"),
+                timing,
+                events,
+                level,
+                function: row.get("function"),
+                body: None,
+                enabled: row.get("enabled"),
+            }
+        })
+        .collect())
+}
+
+/// Tables, types, routines and triggers of one database.
+async fn get_postgres_database(pool: &PgPool, name: String, filter: &SchemaFilter) -> Result<DatabaseInfo, String> {
+    Ok(DatabaseInfo {
+        name,
+        tables: get_postgres_tables(pool, filter).await?,
+        enums: postgres_enums(pool, filter).await?,
+        domains: postgres_domains(pool, filter).await?,
+        sequences: postgres_sequences(pool, filter).await?,
+        routines: postgres_routines(pool, filter).await?,
+        triggers: postgres_triggers(pool, filter).await?,
+        error: None,
+    })
+}
+
+fn postgres_table_kind(relkind: &str) -> TableKind {
+    match relkind {
+        "v" => TableKind::View,
+        "m" => TableKind::MaterializedView,
+        _ => TableKind::Table,
+    }
+}
+
+/// Tables, views and materialized views of one database, with their columns.
+async fn get_postgres_tables(pool: &PgPool, filter: &SchemaFilter) -> Result<Vec<TableInfo>, String> {
+    let tables_query = format!(
+        r#"
+        SELECT 
+            c.relname AS table_name,
+            n.nspname AS table_schema,
+            c.relkind::text AS relkind,
+            CASE WHEN c.relkind IN ('v', 'm') THEN pg_get_viewdef(c.oid) END AS definition,
+            obj_description(c.oid, 'pg_class') AS comment,
+            CASE WHEN c.reltuples >= 0 THEN c.reltuples::bigint END AS row_estimate,
+            CASE WHEN c.relkind <> 'v' THEN pg_total_relation_size(c.oid) END AS total_size,
+            CASE WHEN c.relkind <> 'v' THEN pg_table_size(c.oid) END AS table_size,
+            CASE WHEN c.relkind <> 'v' THEN pg_indexes_size(c.oid) END AS index_size,
+            (extract(epoch FROM greatest(s.last_vacuum, s.last_autovacuum)) * 1000)::bigint AS last_vacuum,
+            (extract(epoch FROM greatest(s.last_analyze, s.last_autoanalyze)) * 1000)::bigint AS last_analyze
+        FROM pg_class c
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        LEFT JOIN pg_stat_user_tables s ON s.relid = c.oid
+        WHERE c.relkind IN ('r', 'p', 'v', 'm')
+        AND {}
+        ORDER BY n.nspname, c.relname
+        "#,
+        postgres_table_condition("n.nspname", "c.relname")
+    );
+    
+    let table_rows = sqlx::query(&tables_query)
+        .bind(filter.include.clone())
+        .bind(filter.exclude.clone())
+        .bind(filter.table.clone())
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to fetch tables: {}", e))?;
+
+    let mut catalog = Catalog {
+        columns: postgres_columns(pool, filter).await?,
+        dependencies: postgres_view_dependencies(pool, filter).await?,
+        indexes: postgres_indexes(pool, filter).await?,
+        constraints: postgres_constraints(pool, filter).await?,
+        foreign_keys: postgres_foreign_keys(pool, filter).await?,
+    };
+
+    Ok(table_rows
+        .into_iter()
+        .map(|row| TableInfo {
+            stats: row.get::<Option<i64>, _>("total_size").map(|total_size| TableStats {
+                row_estimate: row.get("row_estimate"),
+                total_size,
+                table_size: row.get("table_size"),
+                index_size: row.get("index_size"),
+                last_vacuum: row.get("last_vacuum"),
+                last_analyze: row.get("last_analyze"),
+            }),
+            ..catalog.table(
+                row.get("table_schema"),
+                row.get("table_name"),
+                postgres_table_kind(row.get("relkind")),
+                row.get("definition"),
+                row.get("comment"),
+            )
+        })
+        .collect())
+}
+
+pub async fn get_postgres_schema(pool: &PgPool, filter: &SchemaFilter) -> Result<Schema, String> {
+    let tables = get_postgres_tables(pool, filter).await?;
+    Ok(schema_from_tables(tables))
+}
+
+pub async fn get_postgres_databases(session: &Session, filter: &SchemaFilter) -> Result<Vec<DatabaseInfo>, String> {
+    // If no database specified in the connection string, list all databases
+    let specified_db = match &session.database {
+        Some(db) => db.clone(),
+        None => return get_postgres_all_databases(session, filter).await,
+    };
+    
+    let pool = match &session.pool {
+        DbPool::Postgres(pool) => pool,
+        DbPool::MySql(_) => return Err("Not a PostgreSQL connection".to_string()),
+    };
+    
+    // Return the actual database name (Postgres uses "public" as a schema)
+    Ok(vec![get_postgres_database(pool, specified_db, filter).await?])
+}
+
+// Get all databases from PostgreSQL server (when no specific database is provided)
+async fn get_postgres_all_databases(session: &Session, filter: &SchemaFilter) -> Result<Vec<DatabaseInfo>, String> {
+    // pg_database is shared across the cluster, so the session's default database can list it
+    let pool = match &session.pool {
+        DbPool::Postgres(pool) => pool,
+        DbPool::MySql(_) => return Err("Not a PostgreSQL connection".to_string()),
+    };
+    
+    // Get list of user databases (exclude system databases)
+    let db_rows = sqlx::query(
+        r#"
+        SELECT datname 
+        FROM pg_database 
+        WHERE datistemplate = false 
+        AND datname NOT IN ('postgres')
+        ORDER BY datname
+        "#
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to list databases: {}", e))?;
+    
+    // Each database needs its own pool to get its tables; a few are read at a time and
+    // databases that can't be read are listed with the reason
+    let databases = futures_util::stream::iter(db_rows)
+        .map(|db_row| async move {
+            let db_name: String = db_row.get("datname");
+            let database = match session.postgres_database_pool(&db_name).await {
+                Ok(db_pool) => get_postgres_database(&db_pool, db_name.clone(), filter).await,
+                Err(e) => Err(e),
+            };
+            database.unwrap_or_else(|e| DatabaseInfo {
+                name: db_name,
+                tables: Vec::new(),
+                enums: Vec::new(),
+                domains: Vec::new(),
+                sequences: Vec::new(),
+                routines: Vec::new(),
+                triggers: Vec::new(),
+                error: Some(e),
+            })
+        })
+        .buffered(MAX_CONCURRENT_DATABASES)
+        .collect()
+        .await;
+    
+    Ok(databases)
+}
+
+/// The explorer's top level: the connected database, or every user database on the server.
+/// Databases the user can't connect to are listed with the reason.
+pub async fn list_postgres_databases(session: &Session) -> Result<Vec<DatabaseEntry>, String> {
+    if let Some(db) = &session.database {
+        return Ok(vec![DatabaseEntry {
+            name: db.clone(),
+            error: None,
+        }]);
+    }
+
+    let pool = match &session.pool {
+        DbPool::Postgres(pool) => pool,
+        DbPool::MySql(_) => return Err("Not a PostgreSQL connection".to_string()),
+    };
+
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            datname,
+            CASE
+                WHEN NOT datallowconn THEN 'Database does not allow connections'
+                WHEN NOT has_database_privilege(datname, 'CONNECT') THEN 'Permission denied to connect'
+            END AS error
+        FROM pg_database
+        WHERE datistemplate = false
+        AND datname NOT IN ('postgres')
+        ORDER BY datname
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to list databases: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| DatabaseEntry {
+            name: row.get("datname"),
+            error: row.get("error"),
+        })
+        .collect())
+}
+
+/// Schemas of one database that the filter allows.
+pub async fn list_postgres_schemas(pool: &PgPool, filter: &SchemaFilter) -> Result<Vec<SchemaEntry>, String> {
+    let query = format!(
+        r#"
+        SELECT
+            n.nspname AS schema_name,
+            CASE WHEN NOT has_schema_privilege(n.oid, 'USAGE') THEN 'Permission denied for schema' END AS error
+        FROM pg_namespace n
+        WHERE {}
+        ORDER BY n.nspname
+        "#,
+        postgres_schema_condition("n.nspname")
+    );
+
+    let rows = sqlx::query(&query)
+        .bind(filter.include.clone())
+        .bind(filter.exclude.clone())
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to list schemas: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| SchemaEntry {
+            name: row.get("schema_name"),
+            error: row.get("error"),
+        })
+        .collect())
+}
+
+/// Tables, views and materialized views of one schema, without their columns.
+pub async fn list_postgres_tables(pool: &PgPool, schema: &str) -> Result<Vec<TableEntry>, String> {
+    let rows = sqlx::query(
+        r#"
+        SELECT c.relname AS table_name, c.relkind::text AS relkind
+        FROM pg_class c
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        WHERE n.nspname = $1
+        AND c.relkind IN ('r', 'p', 'v', 'm')
+        ORDER BY c.relname
+        "#,
+    )
+    .bind(schema)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to list tables: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| TableEntry {
+            name: row.get("table_name"),
+            schema: Some(schema.to_string()),
+            kind: postgres_table_kind(row.get("relkind")),
+        })
+        .collect())
+}
+
+/// One table with its columns, keys, indexes and constraints.
+pub async fn describe_postgres_table(pool: &PgPool, schema: &str, table: &str) -> Result<TableInfo, String> {
+    let filter = SchemaFilter {
+        include: Some(vec![schema.to_string()]),
+        exclude: Vec::new(),
+        table: Some(table.to_string()),
+    };
+    get_postgres_tables(pool, &filter)
+        .await?
+        .pop()
+        .ok_or_else(|| format!("Table {}.{} not found", schema, table))
+}
+
+/// Routines of one schema, for the explorer.
+pub async fn list_postgres_routines(pool: &PgPool, schema: &str) -> Result<Vec<RoutineInfo>, String> {
+    postgres_routines(pool, &SchemaFilter::new(Some(&vec![schema.to_string()]), None)).await
+}
+
+/// Triggers on the tables of one schema, for the explorer.
+pub async fn list_postgres_triggers(pool: &PgPool, schema: &str) -> Result<Vec<TriggerInfo>, String> {
+    postgres_triggers(pool, &SchemaFilter::new(Some(&vec![schema.to_string()]), None)).await
+}
+
+/// The complete CREATE OR REPLACE statement of a routine, picked by its argument list
+/// among overloads.
+pub async fn postgres_routine_definition(
+    pool: &PgPool,
+    schema: &str,
+    name: &str,
+    arguments: &str,
+) -> Result<String, String> {
+    let row = sqlx::query(
+        r#"
+        SELECT pg_get_functiondef(p.oid) AS definition
+        FROM pg_proc p
+        JOIN pg_namespace n ON n.oid = p.pronamespace
+        WHERE n.nspname = $1
+        AND p.proname = $2
+        AND pg_get_function_arguments(p.oid) = $3
+        "#,
+    )
+    .bind(schema)
+    .bind(name)
+    .bind(arguments)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Failed to fetch definition: {}", e))?;
+
+    row.map(|row| row.get("definition"))
+        .ok_or_else(|| format!("Routine {}.{}({}) not found", schema, name, arguments))
+}
+
+/// The CREATE TRIGGER statement of a trigger. Its logic lives in the trigger function.
+pub async fn postgres_trigger_definition(
+    pool: &PgPool,
+    schema: &str,
+    table: &str,
+    name: &str,
+) -> Result<String, String> {
+    let row = sqlx::query(
+        r#"
+        SELECT pg_get_triggerdef(t.oid, true) AS definition
+        FROM pg_trigger t
+        JOIN pg_class c ON c.oid = t.tgrelid
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        WHERE n.nspname = $1
+        AND c.relname = $2
+        AND t.tgname = $3
+        "#,
+    )
+    .bind(schema)
+    .bind(table)
+    .bind(name)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Failed to fetch definition: {}", e))?;
+
+    row.map(|row| row.get("definition"))
+        .ok_or_else(|| format!("Trigger {} on {}.{} not found", name, schema, table))
+}
+
+pub(super) const MYSQL_SYSTEM_DATABASES: &str = "('information_schema', 'mysql', 'performance_schema', 'sys')";
+
+/// Labels of an `enum('a','b')` column type, which is how MySQL reports enums. Quotes
+/// inside a label are doubled.
+fn mysql_enum_values(column_type: &str) -> Vec<String> {
+    let is_enum = column_type.get(..5).is_some_and(|prefix| prefix.eq_ignore_ascii_case("enum("));
+    let Some(list) = column_type.get(5..).filter(|_| is_enum).and_then(|rest| rest.strip_suffix(')')) else {
+        return Vec::new();
+    };
+
+    let mut values = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = list.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' if in_quotes && chars.peek() == Some(&'\'') => {
+                current.push('\'');
+                chars.next();
+            }
+            '\'' if in_quotes => {
+                in_quotes = false;
+                values.push(std::mem::take(&mut current));
+            }
+            '\'' => in_quotes = true,
+            _ if in_quotes => current.push(c),
+            _ => {}
+        }
+    }
+    values
+}
+
+/// Columns keyed by (database, table), in table order, for one database or all user databases.
+async fn mysql_columns(
+    pool: &MySqlPool,
+    db_name: Option<&str>,
+    table: Option<&str>,
+) -> Result<HashMap<(String, String), Vec<ColumnInfo>>, String> {
+    let query = format!(
+        r#"
+        SELECT 
+            TABLE_SCHEMA,
+            TABLE_NAME,
+            COLUMN_NAME,
+            DATA_TYPE,
+            IS_NULLABLE,
+            COLUMN_DEFAULT,
+            COLUMN_KEY,
+            COLUMN_TYPE,
+            NULLIF(COLUMN_COMMENT, '') AS COLUMN_COMMENT
+        FROM information_schema.COLUMNS
+        WHERE (? IS NULL OR TABLE_SCHEMA = ?)
+        AND (? IS NULL OR TABLE_NAME = ?)
+        AND TABLE_SCHEMA NOT IN {}
+        ORDER BY TABLE_SCHEMA, TABLE_NAME, ORDINAL_POSITION
+        "#,
+        MYSQL_SYSTEM_DATABASES
+    );
+
+    let rows = sqlx::query(&query)
+        .bind(db_name)
+        .bind(db_name)
+        .bind(table)
+        .bind(table)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to fetch columns: {}", e))?;
+
+    let mut columns: HashMap<(String, String), Vec<ColumnInfo>> = HashMap::new();
+    for row in rows {
+        columns
+            .entry((row.get("TABLE_SCHEMA"), row.get("TABLE_NAME")))
+            .or_default()
+            .push(ColumnInfo {
+                name: row.get("COLUMN_NAME"),
+                column_type: row.get("DATA_TYPE"),
+                nullable: row.get::<String, _>("IS_NULLABLE") == "YES",
+                is_primary_key: row.get::<String, _>("COLUMN_KEY") == "PRI",
+                is_foreign_key: false,
+                foreign_key_target_table: None,
+                foreign_key_target_column: None,
+                foreign_key_target_schema: None,
+                enum_values: mysql_enum_values(&row.get::<String, _>("COLUMN_TYPE")),
+                default_value: row.get("COLUMN_DEFAULT"),
+                comment: row.get("COLUMN_COMMENT"),
+            });
+    }
+    Ok(columns)
+}
+
+/// Indexes keyed by (database, table), for one database or all user databases.
+async fn mysql_indexes(
+    pool: &MySqlPool,
+    db_name: Option<&str>,
+    table: Option<&str>,
+) -> Result<HashMap<(String, String), Vec<IndexInfo>>, String> {
+    let query = format!(
+        r#"
+        SELECT
+            TABLE_SCHEMA,
+            TABLE_NAME,
+            INDEX_NAME,
+            CAST(NON_UNIQUE AS SIGNED) AS NON_UNIQUE,
+            INDEX_TYPE,
+            COLUMN_NAME
+        FROM information_schema.STATISTICS
+        WHERE (? IS NULL OR TABLE_SCHEMA = ?)
+        AND (? IS NULL OR TABLE_NAME = ?)
+        AND TABLE_SCHEMA NOT IN {}
+        ORDER BY TABLE_SCHEMA, TABLE_NAME, INDEX_NAME, SEQ_IN_INDEX
+        "#,
+        MYSQL_SYSTEM_DATABASES
+    );
+
+    let rows = sqlx::query(&query)
+        .bind(db_name)
+        .bind(db_name)
+        .bind(table)
+        .bind(table)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to fetch indexes: {}", e))?;
+
+    // STATISTICS has one row per index column
+    let mut indexes: HashMap<(String, String), Vec<IndexInfo>> = HashMap::new();
+    for row in rows {
+        let table_indexes = indexes
+            .entry((row.get("TABLE_SCHEMA"), row.get("TABLE_NAME")))
+            .or_default();
+        let name: String = row.get("INDEX_NAME");
+        // Functional key parts (MySQL 8.0.13+) have no column name
+        let column = row
+            .get::<Option<String>, _>("COLUMN_NAME")
+            .unwrap_or_else(|| "(expression)".to_string());
+
+        match table_indexes.last_mut() {
+            Some(index) if index.name == name => index.columns.push(column),
+            _ => table_indexes.push(IndexInfo {
+                is_primary: name == "PRIMARY",
+                name,
+                columns: vec![column],
+                is_unique: row.get::<i64, _>("NON_UNIQUE") == 0,
+                method: row.get("INDEX_TYPE"),
+                predicate: None,
+            }),
+        }
+    }
+    Ok(indexes)
+}
+
+/// Unique and check constraints keyed by (database, table). CHECK_CONSTRAINTS is missing
+/// before MySQL 8.0.16 / MariaDB 10.2, where tables come back without check constraints.
+async fn mysql_constraints(
+    pool: &MySqlPool,
+    db_name: Option<&str>,
+    table: Option<&str>,
+) -> Result<HashMap<(String, String), TableConstraints>, String> {
+    let unique_query = format!(
+        r#"
+        SELECT
+            tc.TABLE_SCHEMA,
+            tc.TABLE_NAME,
+            tc.CONSTRAINT_NAME,
+            kcu.COLUMN_NAME
+        FROM information_schema.TABLE_CONSTRAINTS tc
+        JOIN information_schema.KEY_COLUMN_USAGE kcu
+            ON kcu.CONSTRAINT_SCHEMA = tc.CONSTRAINT_SCHEMA
+            AND kcu.CONSTRAINT_NAME = tc.CONSTRAINT_NAME
+            AND kcu.TABLE_NAME = tc.TABLE_NAME
+        WHERE tc.CONSTRAINT_TYPE = 'UNIQUE'
+        AND (? IS NULL OR tc.TABLE_SCHEMA = ?)
+        AND (? IS NULL OR tc.TABLE_NAME = ?)
+        AND tc.TABLE_SCHEMA NOT IN {}
+        ORDER BY tc.TABLE_SCHEMA, tc.TABLE_NAME, tc.CONSTRAINT_NAME, kcu.ORDINAL_POSITION
+        "#,
+        MYSQL_SYSTEM_DATABASES
+    );
+
+    let unique_rows = sqlx::query(&unique_query)
+        .bind(db_name)
+        .bind(db_name)
+        .bind(table)
+        .bind(table)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to fetch constraints: {}", e))?;
+
+    let mut constraints: HashMap<(String, String), TableConstraints> = HashMap::new();
+    for row in unique_rows {
+        let unique = &mut constraints
+            .entry((row.get("TABLE_SCHEMA"), row.get("TABLE_NAME")))
+            .or_default()
+            .unique;
+        let name: String = row.get("CONSTRAINT_NAME");
+        let column: String = row.get("COLUMN_NAME");
+        match unique.last_mut() {
+            Some(constraint) if constraint.name == name => constraint.columns.push(column),
+            _ => unique.push(UniqueConstraint {
+                name,
+                columns: vec![column],
+            }),
+        }
+    }
+
+    let check_query = format!(
+        r#"
+        SELECT
+            tc.TABLE_SCHEMA,
+            tc.TABLE_NAME,
+            tc.CONSTRAINT_NAME,
+            cc.CHECK_CLAUSE
+        FROM information_schema.TABLE_CONSTRAINTS tc
+        JOIN information_schema.CHECK_CONSTRAINTS cc
+            ON cc.CONSTRAINT_SCHEMA = tc.CONSTRAINT_SCHEMA
+            AND cc.CONSTRAINT_NAME = tc.CONSTRAINT_NAME
+        WHERE tc.CONSTRAINT_TYPE = 'CHECK'
+        AND (? IS NULL OR tc.TABLE_SCHEMA = ?)
+        AND (? IS NULL OR tc.TABLE_NAME = ?)
+        AND tc.TABLE_SCHEMA NOT IN {}
+        ORDER BY tc.TABLE_SCHEMA, tc.TABLE_NAME, tc.CONSTRAINT_NAME
+        "#,
+        MYSQL_SYSTEM_DATABASES
+    );
+
+    if let Ok(check_rows) = sqlx::query(&check_query)
+        .bind(db_name)
+        .bind(db_name)
+        .bind(table)
+        .bind(table)
+        .fetch_all(pool)
+        .await
+    {
+        for row in check_rows {
+            constraints
+                .entry((row.get("TABLE_SCHEMA"), row.get("TABLE_NAME")))
+                .or_default()
+                .check
+                .push(CheckConstraint {
+                    name: row.get("CONSTRAINT_NAME"),
+                    expression: row.get("CHECK_CLAUSE"),
+                });
+        }
+    }
+    Ok(constraints)
+}
+
+/// Foreign keys of one database or all user databases, from the REFERENCED_* columns of
+/// KEY_COLUMN_USAGE.
+async fn mysql_foreign_keys(
+    pool: &MySqlPool,
+    db_name: Option<&str>,
+    table: Option<&str>,
+) -> Result<ForeignKeys, String> {
+    let query = format!(
+        r#"
+        SELECT
+            kcu.TABLE_SCHEMA,
+            kcu.TABLE_NAME,
+            kcu.CONSTRAINT_NAME,
+            kcu.COLUMN_NAME,
+            kcu.REFERENCED_TABLE_SCHEMA,
+            kcu.REFERENCED_TABLE_NAME,
+            kcu.REFERENCED_COLUMN_NAME,
+            rc.UPDATE_RULE,
+            rc.DELETE_RULE
+        FROM information_schema.KEY_COLUMN_USAGE kcu
+        JOIN information_schema.REFERENTIAL_CONSTRAINTS rc
+            ON rc.CONSTRAINT_SCHEMA = kcu.CONSTRAINT_SCHEMA
+            AND rc.CONSTRAINT_NAME = kcu.CONSTRAINT_NAME
+            AND rc.TABLE_NAME = kcu.TABLE_NAME
+        WHERE kcu.REFERENCED_TABLE_NAME IS NOT NULL
+        AND (? IS NULL OR kcu.TABLE_SCHEMA = ?)
+        AND (? IS NULL OR kcu.TABLE_NAME = ?)
+        AND kcu.TABLE_SCHEMA NOT IN {}
+        ORDER BY kcu.TABLE_SCHEMA, kcu.TABLE_NAME, kcu.CONSTRAINT_NAME, kcu.ORDINAL_POSITION
+        "#,
+        MYSQL_SYSTEM_DATABASES
+    );
+
+    let rows = sqlx::query(&query)
+        .bind(db_name)
+        .bind(db_name)
+        .bind(table)
+        .bind(table)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to fetch foreign keys: {}", e))?;
+
+    // NO ACTION is the default and is left out, as for Postgres
+    let rule = |rule: String| (rule != "NO ACTION").then_some(rule);
+
+    // KEY_COLUMN_USAGE has one row per key column
+    let mut foreign_keys = ForeignKeys::new();
+    for row in rows {
+        let schema_name: String = row.get("TABLE_SCHEMA");
+        let target_schema: String = row.get("REFERENCED_TABLE_SCHEMA");
+        let name: String = row.get("CONSTRAINT_NAME");
+        let column: String = row.get("COLUMN_NAME");
+        let target_column: String = row.get("REFERENCED_COLUMN_NAME");
+
+        let table_keys = foreign_keys
+            .entry((schema_name.clone(), row.get("TABLE_NAME")))
+            .or_default();
+        match table_keys.last_mut() {
+            Some(fk) if fk.name.as_deref() == Some(name.as_str()) => {
+                fk.columns.push(column);
+                fk.target_columns.push(target_column);
+            }
+            _ => table_keys.push(ForeignKey {
+                name: Some(name),
+                columns: vec![column],
+                target_schema: (target_schema != schema_name).then_some(target_schema),
+                target_table: row.get("REFERENCED_TABLE_NAME"),
+                target_columns: vec![target_column],
+                on_delete: rule(row.get("DELETE_RULE")),
+                on_update: rule(row.get("UPDATE_RULE")),
+            }),
+        }
+    }
+    Ok(foreign_keys)
+}
+
+/// What each view reads from, keyed by (database, view). VIEW_TABLE_USAGE only exists
+/// on MySQL 8.0.13+, so on MariaDB and older servers views come back without dependencies.
+async fn mysql_view_dependencies(
+    pool: &MySqlPool,
+    db_name: Option<&str>,
+    table: Option<&str>,
+) -> HashMap<(String, String), Vec<TableRef>> {
+    let query = r#"
+        SELECT VIEW_SCHEMA, VIEW_NAME, TABLE_SCHEMA, TABLE_NAME
+        FROM information_schema.VIEW_TABLE_USAGE
+        WHERE (? IS NULL OR VIEW_SCHEMA = ?)
+        AND (? IS NULL OR VIEW_NAME = ?)
+        ORDER BY VIEW_SCHEMA, VIEW_NAME, TABLE_SCHEMA, TABLE_NAME
+    "#;
+
+    let mut dependencies: HashMap<(String, String), Vec<TableRef>> = HashMap::new();
+    let Ok(rows) = sqlx::query(query).bind(db_name).bind(db_name).bind(table).bind(table).fetch_all(pool).await else {
+        return dependencies;
+    };
+    for row in rows {
+        dependencies
+            .entry((row.get("VIEW_SCHEMA"), row.get("VIEW_NAME")))
+            .or_default()
+            .push(TableRef {
+                schema: Some(row.get("TABLE_SCHEMA")),
+                name: row.get("TABLE_NAME"),
+            });
+    }
+    dependencies
+}
+
+/// Stored functions and procedures of one database or all user databases. The argument
+/// list is rebuilt from PARAMETERS the way it is declared.
+async fn mysql_routines(pool: &MySqlPool, db_name: Option<&str>) -> Result<Vec<RoutineInfo>, String> {
+    let query = format!(
+        r#"
+        SELECT
+            r.ROUTINE_SCHEMA,
+            r.ROUTINE_NAME,
+            r.ROUTINE_TYPE,
+            r.DTD_IDENTIFIER,
+            r.ROUTINE_BODY,
+            r.ROUTINE_DEFINITION,
+            (
+                SELECT GROUP_CONCAT(
+                    CONCAT_WS(' ', p.PARAMETER_MODE, p.PARAMETER_NAME, p.DTD_IDENTIFIER)
+                    ORDER BY p.ORDINAL_POSITION SEPARATOR ', '
+                )
+                FROM information_schema.PARAMETERS p
+                WHERE p.SPECIFIC_SCHEMA = r.ROUTINE_SCHEMA
+                AND p.SPECIFIC_NAME = r.SPECIFIC_NAME
+                AND p.ROUTINE_TYPE = r.ROUTINE_TYPE
+                AND p.ORDINAL_POSITION > 0
+            ) AS ARGUMENTS
+        FROM information_schema.ROUTINES r
+        WHERE (? IS NULL OR r.ROUTINE_SCHEMA = ?)
+        AND r.ROUTINE_SCHEMA NOT IN {}
+        ORDER BY r.ROUTINE_SCHEMA, r.ROUTINE_NAME
+        "#,
+        MYSQL_SYSTEM_DATABASES
+    );
+
+    let rows = sqlx::query(&query)
+        .bind(db_name)
+        .bind(db_name)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to fetch routines: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let routine_type: String = row.get("ROUTINE_TYPE");
+            let is_procedure = routine_type == "PROCEDURE";
+            RoutineInfo {
+                schema: Some(row.get("ROUTINE_SCHEMA")),
+                name: row.get("ROUTINE_NAME"),
+                kind: if is_procedure { RoutineKind::Procedure } else { RoutineKind::Function },
+                arguments: row.get::<Option<String>, _>("ARGUMENTS").unwrap_or_default(),
+                return_type: row.get::<Option<String>, _>("DTD_IDENTIFIER").filter(|_| !is_procedure),
+                language: row.get("ROUTINE_BODY"),
+                // Only the definer or a user with SHOW_ROUTINE can see it
+                body: row.get("ROUTINE_DEFINITION"),
+            }
+        })
+        .collect())
+}
+
+/// Triggers of one database or all user databases. MySQL triggers fire for one event,
+/// row by row.
+async fn mysql_triggers(pool: &MySqlPool, db_name: Option<&str>) -> Result<Vec<TriggerInfo>, String> {
+    let query = format!(
+        r#"
+        SELECT
+            TRIGGER_SCHEMA,
+            TRIGGER_NAME,
+            EVENT_OBJECT_TABLE,
+            ACTION_TIMING,
+            EVENT_MANIPULATION,
+            ACTION_ORIENTATION,
+            ACTION_STATEMENT
+        FROM information_schema.TRIGGERS
+        WHERE (? IS NULL OR TRIGGER_SCHEMA = ?)
+        AND TRIGGER_SCHEMA NOT IN {}
+        ORDER BY TRIGGER_SCHEMA, EVENT_OBJECT_TABLE, ACTION_ORDER
+        "#,
+        MYSQL_SYSTEM_DATABASES
+    );
+
+    let rows = sqlx::query(&query)
+        .bind(db_name)
+        .bind(db_name)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to fetch triggers: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| TriggerInfo {
+            schema: Some(row.get("TRIGGER_SCHEMA")),
+            name: row.get("TRIGGER_NAME"),
+            table: row.get("EVENT_OBJECT_TABLE"),
+            timing: row.get("ACTION_TIMING"),
+            events: vec![row.get("EVENT_MANIPULATION")],
+            level: row.get("ACTION_ORIENTATION"),
+            function: None,
+            body: Some(row.get("ACTION_STATEMENT")),
+            enabled: true,
+        })
+        .collect())
+}
+
+/// Tables and views of one database, or of all user databases, with their columns. Only
+/// the named table is read when `table` is set.
+async fn get_mysql_tables(
+    pool: &MySqlPool,
+    db_name: Option<&str>,
+    table: Option<&str>,
+) -> Result<Vec<TableInfo>, String> {
+    let tables_query = format!(
+        r#"
+        SELECT
+            t.TABLE_SCHEMA,
+            t.TABLE_NAME,
+            t.TABLE_TYPE,
+            v.VIEW_DEFINITION,
+            -- Views report 'VIEW' as their comment
+            CASE WHEN t.TABLE_TYPE = 'VIEW' THEN NULL ELSE NULLIF(t.TABLE_COMMENT, '') END AS TABLE_COMMENT,
+            -- Estimates, cached for information_schema_stats_expiry seconds
+            CAST(t.TABLE_ROWS AS SIGNED) AS TABLE_ROWS,
+            CAST(t.DATA_LENGTH AS SIGNED) AS DATA_LENGTH,
+            CAST(t.INDEX_LENGTH AS SIGNED) AS INDEX_LENGTH
+        FROM information_schema.TABLES t
+        LEFT JOIN information_schema.VIEWS v
+            ON v.TABLE_SCHEMA = t.TABLE_SCHEMA AND v.TABLE_NAME = t.TABLE_NAME
+        WHERE (? IS NULL OR t.TABLE_SCHEMA = ?)
+        AND (? IS NULL OR t.TABLE_NAME = ?)
+        AND t.TABLE_SCHEMA NOT IN {}
+        AND t.TABLE_TYPE IN ('BASE TABLE', 'VIEW')
+        ORDER BY t.TABLE_SCHEMA, t.TABLE_NAME
+        "#,
+        MYSQL_SYSTEM_DATABASES
+    );
+    
+    let table_rows = sqlx::query(&tables_query)
+        .bind(db_name)
+        .bind(db_name)
+        .bind(table)
+        .bind(table)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to fetch tables: {}", e))?;
+
+    let mut catalog = Catalog {
+        columns: mysql_columns(pool, db_name, table).await?,
+        dependencies: mysql_view_dependencies(pool, db_name, table).await,
+        indexes: mysql_indexes(pool, db_name, table).await?,
+        constraints: mysql_constraints(pool, db_name, table).await?,
+        foreign_keys: mysql_foreign_keys(pool, db_name, table).await?,
+    };
+
+    Ok(table_rows
+        .into_iter()
+        .map(|row| {
+            let table_type: String = row.get("TABLE_TYPE");
+            let index_size: Option<i64> = row.get("INDEX_LENGTH");
+            let stats = row.get::<Option<i64>, _>("DATA_LENGTH").map(|table_size| TableStats {
+                row_estimate: row.get("TABLE_ROWS"),
+                total_size: table_size + index_size.unwrap_or(0),
+                table_size,
+                index_size: index_size.unwrap_or(0),
+                last_vacuum: None,
+                last_analyze: None,
+            });
+            TableInfo {
+                stats,
+                ..catalog.table(
+                    row.get("TABLE_SCHEMA"),
+                    row.get("TABLE_NAME"),
+                    if table_type == "VIEW" { TableKind::View } else { TableKind::Table },
+                    row.get("VIEW_DEFINITION"),
+                    row.get("TABLE_COMMENT"),
+                )
+            }
+        })
+        .collect())
+}
+
+pub async fn get_mysql_schema(pool: &MySqlPool, database_name: &str) -> Result<Schema, String> {
+    // If no database specified, get schema from all user databases
+    let database = Some(database_name).filter(|db| !db.is_empty());
+    let tables = get_mysql_tables(pool, database, None).await?;
+    Ok(schema_from_tables(tables))
+}
+
+pub async fn get_mysql_databases(pool: &MySqlPool, db_name: &str) -> Result<Vec<DatabaseInfo>, String> {
+    // If no database specified, list all databases
+    if db_name.is_empty() {
+        return get_mysql_all_databases(pool).await;
+    }
+    
+    let tables = get_mysql_tables(pool, Some(db_name), None).await?;
+    
+    // MySQL enums live on their columns, and it has no domains or sequences
+    Ok(vec![DatabaseInfo {
+        name: db_name.to_string(),
+        tables,
+        enums: Vec::new(),
+        domains: Vec::new(),
+        sequences: Vec::new(),
+        routines: mysql_routines(pool, Some(db_name)).await?,
+        triggers: mysql_triggers(pool, Some(db_name)).await?,
+        error: None,
+    }])
+}
+
+// Get all databases from MySQL server (when no specific database is provided)
+async fn get_mysql_all_databases(pool: &MySqlPool) -> Result<Vec<DatabaseInfo>, String> {
+    // Get list of user databases (exclude system databases)
+    let db_rows = sqlx::query(&format!(
+        r#"
+        SELECT SCHEMA_NAME 
+        FROM information_schema.SCHEMATA 
+        WHERE SCHEMA_NAME NOT IN {}
+        ORDER BY SCHEMA_NAME
+        "#,
+        MYSQL_SYSTEM_DATABASES
+    ))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to list databases: {}", e))?;
+    
+    // One pass over information_schema covers every database
+    let mut tables_by_database: HashMap<String, Vec<TableInfo>> = HashMap::new();
+    for table in get_mysql_tables(pool, None, None).await? {
+        tables_by_database
+            .entry(table.schema.clone().unwrap_or_default())
+            .or_default()
+            .push(table);
+    }
+    let mut routines_by_database: HashMap<String, Vec<RoutineInfo>> = HashMap::new();
+    for routine in mysql_routines(pool, None).await? {
+        routines_by_database
+            .entry(routine.schema.clone().unwrap_or_default())
+            .or_default()
+            .push(routine);
+    }
+    let mut triggers_by_database: HashMap<String, Vec<TriggerInfo>> = HashMap::new();
+    for trigger in mysql_triggers(pool, None).await? {
+        triggers_by_database
+            .entry(trigger.schema.clone().unwrap_or_default())
+            .or_default()
+            .push(trigger);
+    }
+    
+    let names: Vec<String> = db_rows.iter().map(|row| row.get("SCHEMA_NAME")).collect();
+    let errors = mysql_database_errors(pool, &names).await;
+    let databases = names
+        .into_iter()
+        .zip(errors)
+        .map(|(db_name, error)| DatabaseInfo {
+            tables: tables_by_database.remove(&db_name).unwrap_or_default(),
+            routines: routines_by_database.remove(&db_name).unwrap_or_default(),
+            triggers: triggers_by_database.remove(&db_name).unwrap_or_default(),
+            name: db_name,
+            enums: Vec::new(),
+            domains: Vec::new(),
+            sequences: Vec::new(),
+            error,
+        })
+        .collect();
+    
+    Ok(databases)
+}
+
+/// Why each database's tables can't be read, if they can't. A user with SHOW DATABASES sees
+/// every database in SCHEMATA, including ones they hold no other privilege on, and
+/// information_schema just comes back empty for those rather than failing.
+async fn mysql_database_errors(pool: &MySqlPool, names: &[String]) -> Vec<Option<String>> {
+    futures_util::stream::iter(names)
+        .map(|name| async move {
+            let query = format!("SHOW FULL TABLES FROM `{}`", name.replace('`', "``"));
+            pool.fetch_optional(query.as_str())
+                .await
+                .err()
+                .map(|e| format!("Failed to read database: {}", e))
+        })
+        .buffered(MAX_CONCURRENT_DATABASES)
+        .collect()
+        .await
+}
+
+/// The explorer's top level: the connected database, or every user database on the server.
+/// Databases whose tables the user can't read are listed with the reason.
+pub async fn list_mysql_databases(pool: &MySqlPool, db_name: &str) -> Result<Vec<DatabaseEntry>, String> {
+    if !db_name.is_empty() {
+        return Ok(vec![DatabaseEntry {
+            name: db_name.to_string(),
+            error: None,
+        }]);
+    }
+
+    // SCHEMATA only shows databases the user has some privilege on
+    let rows = sqlx::query(&format!(
+        r#"
+        SELECT SCHEMA_NAME
+        FROM information_schema.SCHEMATA
+        WHERE SCHEMA_NAME NOT IN {}
+        ORDER BY SCHEMA_NAME
+        "#,
+        MYSQL_SYSTEM_DATABASES
+    ))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to list databases: {}", e))?;
+
+    let names: Vec<String> = rows.iter().map(|row| row.get("SCHEMA_NAME")).collect();
+    let errors = mysql_database_errors(pool, &names).await;
+    Ok(names
+        .into_iter()
+        .zip(errors)
+        .map(|(name, error)| DatabaseEntry { name, error })
+        .collect())
+}
+
+/// Tables and views of one database, without their columns.
+pub async fn list_mysql_tables(pool: &MySqlPool, db_name: &str) -> Result<Vec<TableEntry>, String> {
+    let rows = sqlx::query(
+        r#"
+        SELECT TABLE_NAME, TABLE_TYPE
+        FROM information_schema.TABLES
+        WHERE TABLE_SCHEMA = ?
+        AND TABLE_TYPE IN ('BASE TABLE', 'VIEW')
+        ORDER BY TABLE_NAME
+        "#,
+    )
+    .bind(db_name)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to list tables: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let table_type: String = row.get("TABLE_TYPE");
+            TableEntry {
+                name: row.get("TABLE_NAME"),
+                schema: Some(db_name.to_string()),
+                kind: if table_type == "VIEW" { TableKind::View } else { TableKind::Table },
+            }
+        })
+        .collect())
+}
+
+/// One table with its columns, keys, indexes and constraints.
+pub async fn describe_mysql_table(pool: &MySqlPool, db_name: &str, table: &str) -> Result<TableInfo, String> {
+    get_mysql_tables(pool, Some(db_name), Some(table))
+        .await?
+        .pop()
+        .ok_or_else(|| format!("Table {}.{} not found", db_name, table))
+}
+
+/// Routines of one database, for the explorer.
+pub async fn list_mysql_routines(pool: &MySqlPool, db_name: &str) -> Result<Vec<RoutineInfo>, String> {
+    mysql_routines(pool, Some(db_name)).await
+}
+
+/// Triggers of one database, for the explorer.
+pub async fn list_mysql_triggers(pool: &MySqlPool, db_name: &str) -> Result<Vec<TriggerInfo>, String> {
+    mysql_triggers(pool, Some(db_name)).await
+}
+
+fn mysql_identifier(name: &str) -> String {
+    format!("`{}`", name.replace('`', "``"))
+}
+
+/// Runs a SHOW CREATE statement and reads the named column of its single row. The column
+/// is NULL when the user lacks the privilege to see the definition.
+async fn mysql_show_create(pool: &MySqlPool, statement: &str, column: &str) -> Result<String, String> {
+    // SHOW CREATE TRIGGER can't be prepared, so this goes over the text protocol
+    let row = sqlx::raw_sql(statement)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to fetch definition: {}", e))?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Definition not found".to_string())?;
+    row.try_get::<Option<String>, _>(column)
+        .map_err(|e| format!("Failed to read definition: {}", e))?
+        .ok_or_else(|| "Permission denied to view the definition".to_string())
+}
+
+/// The complete CREATE statement of a stored function or procedure.
+pub async fn mysql_routine_definition(
+    pool: &MySqlPool,
+    db_name: &str,
+    name: &str,
+    kind: RoutineKind,
+) -> Result<String, String> {
+    let (keyword, column) = match kind {
+        RoutineKind::Function => ("FUNCTION", "Create Function"),
+        RoutineKind::Procedure => ("PROCEDURE", "Create Procedure"),
+    };
+    let statement = format!(
+        "SHOW CREATE {} {}.{}",
+        keyword,
+        mysql_identifier(db_name),
+        mysql_identifier(name)
+    );
+    mysql_show_create(pool, &statement, column).await
+}
+
+/// The complete CREATE TRIGGER statement of a trigger.
+pub async fn mysql_trigger_definition(pool: &MySqlPool, db_name: &str, name: &str) -> Result<String, String> {
+    let statement = format!("SHOW CREATE TRIGGER {}.{}", mysql_identifier(db_name), mysql_identifier(name));
+    mysql_show_create(pool, &statement, "SQL Original Statement").await
+}
@@ -1,5 +1,19 @@
 pub mod parser;
 pub mod connection;
+pub mod decode;
+pub mod digest;
+pub mod errors;
+pub mod executor;
+pub mod explain;
+pub mod introspect;
+pub mod params;
+pub mod registry;
+pub mod script;
+pub mod snapshot;
 
 pub use parser::parse_sql_to_schema;
 pub use connection::*;
+pub use introspect::*;
+pub use executor::{execute_script, explain_statement, run_transaction_statement, script_parameters, DEFAULT_PAGE_SIZE};
+pub use registry::*;
+pub use snapshot::SchemaCache;
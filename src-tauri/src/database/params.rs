@@ -0,0 +1,310 @@
+use std::collections::HashMap;
+use std::ops::ControlFlow;
+use std::str::FromStr;
+
+use sqlparser::ast::{visit_expressions, visit_expressions_mut, Expr, Statement, Value};
+use sqlx::mysql::{MySql, MySqlArguments};
+use sqlx::postgres::types::PgTimeTz;
+use sqlx::postgres::{PgArguments, PgTypeInfo, PgTypeKind, Postgres};
+use sqlx::query::Query;
+use sqlx::types::chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use sqlx::types::ipnetwork::IpNetwork;
+use sqlx::types::{BigDecimal, Json, Uuid};
+use sqlx::TypeInfo;
+
+use crate::types::QueryParameter;
+
+/// Name a placeholder is bound by: `:name` and `$1` as written, and bare `?` numbered by
+/// its position in the script (`?1`, `?2`, ...). Returns None for things that parse as
+/// placeholders but aren't parameters (e.g. `@var`).
+fn placeholder_name(placeholder: &str, positional: &mut usize) -> Option<String> {
+    if placeholder == "?" {
+        *positional += 1;
+        return Some(format!("?{}", positional));
+    }
+    match placeholder.chars().next() {
+        Some(':') | Some('$') | Some('?') => Some(placeholder.to_string()),
+        _ => None,
+    }
+}
+
+/// Distinct parameters of a script, in order of first appearance.
+pub fn find_parameters(statements: &[Statement]) -> Vec<QueryParameter> {
+    let mut positional = 0;
+    let mut names: Vec<String> = Vec::new();
+
+    for statement in statements {
+        let _ = visit_expressions(statement, |expr| {
+            if let Expr::Value(Value::Placeholder(placeholder)) = expr {
+                if let Some(name) = placeholder_name(placeholder, &mut positional) {
+                    if !names.contains(&name) {
+                        names.push(name);
+                    }
+                }
+            }
+            ControlFlow::<()>::Continue(())
+        });
+    }
+
+    names
+        .into_iter()
+        .map(|name| QueryParameter {
+            kind: if name.starts_with(':') { "named" } else { "positional" }.to_string(),
+            name,
+        })
+        .collect()
+}
+
+/// Rewrites a statement's placeholders into the form the driver binds (`$n` for Postgres,
+/// `?` for MySQL) and returns the values to bind, in order. `positional` carries the
+/// numbering of bare `?` across the statements of a script.
+pub fn bind_placeholders(
+    statement: &mut Statement,
+    numbered: bool,
+    values: &HashMap<String, serde_json::Value>,
+    positional: &mut usize,
+) -> Result<Vec<serde_json::Value>, String> {
+    let mut order: Vec<String> = Vec::new();
+    let mut missing = None;
+
+    let _ = visit_expressions_mut(statement, |expr| {
+        if let Expr::Value(Value::Placeholder(placeholder)) = expr {
+            let Some(name) = placeholder_name(placeholder, positional) else {
+                return ControlFlow::Continue(());
+            };
+            if !values.contains_key(&name) {
+                missing = Some(name);
+                return ControlFlow::Break(());
+            }
+
+            if numbered {
+                // Postgres can reference the same $n twice, so each name is bound once
+                let index = match order.iter().position(|n| *n == name) {
+                    Some(i) => i + 1,
+                    None => {
+                        order.push(name);
+                        order.len()
+                    }
+                };
+                *placeholder = format!("${}", index);
+            } else {
+                order.push(name);
+                *placeholder = "?".to_string();
+            }
+        }
+        ControlFlow::Continue(())
+    });
+
+    if let Some(name) = missing {
+        return Err(format!("Missing value for parameter {}", name));
+    }
+    Ok(order.iter().map(|name| values[name].clone()).collect())
+}
+
+fn json_i64(value: &serde_json::Value) -> Option<i64> {
+    match value {
+        serde_json::Value::Number(n) => n.as_i64(),
+        serde_json::Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn json_f64(value: &serde_json::Value) -> Option<f64> {
+    match value {
+        serde_json::Value::Number(n) => n.as_f64(),
+        serde_json::Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn json_bool(value: &serde_json::Value) -> Option<bool> {
+    match value {
+        serde_json::Value::Bool(b) => Some(*b),
+        serde_json::Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn json_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn parse_timestamp(text: &str) -> Option<NaiveDateTime> {
+    let text = text.trim();
+    NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S%.f"))
+        .ok()
+        .or_else(|| NaiveDate::parse_from_str(text, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0))
+}
+
+fn json_i16(value: &serde_json::Value) -> Option<i16> {
+    i16::try_from(json_i64(value)?).ok()
+}
+
+fn json_i32(value: &serde_json::Value) -> Option<i32> {
+    i32::try_from(json_i64(value)?).ok()
+}
+
+fn json_f32(value: &serde_json::Value) -> Option<f32> {
+    json_f64(value).map(|n| n as f32)
+}
+
+fn json_string(value: &serde_json::Value) -> Option<String> {
+    (!value.is_null()).then(|| json_text(value))
+}
+
+fn json_numeric(value: &serde_json::Value) -> Option<BigDecimal> {
+    BigDecimal::from_str(json_text(value).trim()).ok()
+}
+
+fn json_uuid(value: &serde_json::Value) -> Option<Uuid> {
+    Uuid::parse_str(json_text(value).trim()).ok()
+}
+
+fn json_date(value: &serde_json::Value) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(json_text(value).trim(), "%Y-%m-%d").ok()
+}
+
+fn json_time(value: &serde_json::Value) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(json_text(value).trim(), "%H:%M:%S%.f").ok()
+}
+
+// "12:30:00+02", "12:30:00.5-05:30"
+fn json_timetz(value: &serde_json::Value) -> Option<PgTimeTz<NaiveTime, FixedOffset>> {
+    let text = format!("1970-01-01 {}", json_text(value).trim());
+    let time = DateTime::parse_from_str(&text, "%Y-%m-%d %H:%M:%S%.f%#z").ok()?;
+    Some(PgTimeTz {
+        time: time.time(),
+        offset: *time.offset(),
+    })
+}
+
+fn json_timestamp(value: &serde_json::Value) -> Option<NaiveDateTime> {
+    parse_timestamp(&json_text(value))
+}
+
+fn json_timestamptz(value: &serde_json::Value) -> Option<DateTime<Utc>> {
+    let text = json_text(value);
+    DateTime::parse_from_rfc3339(text.trim())
+        .map(|t| t.with_timezone(&Utc))
+        .ok()
+        .or_else(|| parse_timestamp(&text).map(|t| t.and_utc()))
+}
+
+fn json_ip_network(value: &serde_json::Value) -> Option<IpNetwork> {
+    IpNetwork::from_str(json_text(value).trim()).ok()
+}
+
+/// Bytes from Postgres' hex form (`\xdeadbeef`), an array of byte values, or else the UTF-8
+/// of the text.
+fn json_bytes(value: &serde_json::Value) -> Option<Vec<u8>> {
+    if let serde_json::Value::Array(items) = value {
+        return items.iter().map(|v| u8::try_from(v.as_u64()?).ok()).collect();
+    }
+    let text = json_text(value);
+    let Some(hex) = text.strip_prefix("\\x") else {
+        return Some(text.into_bytes());
+    };
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// A JSON array whose elements all convert. NULL elements aren't supported.
+fn json_array<T>(value: &serde_json::Value, f: impl Fn(&serde_json::Value) -> Option<T>) -> Option<Vec<T>> {
+    value.as_array()?.iter().map(f).collect()
+}
+
+/// Converts a non-null value, or returns None for SQL NULL. Err means the value doesn't fit.
+fn convert<T>(value: &serde_json::Value, f: impl Fn(&serde_json::Value) -> Option<T>) -> Result<Option<T>, ()> {
+    if value.is_null() {
+        return Ok(None);
+    }
+    f(value).map(Some).ok_or(())
+}
+
+/// Binds a parameter as the type Postgres inferred for it when preparing the statement,
+/// so `$1` compared with an int4 column is sent as an int4. Domains are bound as their base
+/// type. Types without a binding here are refused rather than sent as text, which the server
+/// would read in the wrong wire format.
+pub fn bind_postgres_value<'q>(
+    query: Query<'q, Postgres, PgArguments>,
+    index: usize,
+    value: &serde_json::Value,
+    type_info: &PgTypeInfo,
+) -> Result<Query<'q, Postgres, PgArguments>, String> {
+    let mut base = type_info;
+    while let PgTypeKind::Domain(inner) = base.kind() {
+        base = inner;
+    }
+    let type_name = type_info.name();
+    let bound = match base.name() {
+        "BOOL" => convert(value, json_bool).map(|v| query.bind(v)),
+        "INT2" => convert(value, json_i16).map(|v| query.bind(v)),
+        "INT4" => convert(value, json_i32).map(|v| query.bind(v)),
+        "INT8" => convert(value, json_i64).map(|v| query.bind(v)),
+        "FLOAT4" => convert(value, json_f32).map(|v| query.bind(v)),
+        "FLOAT8" => convert(value, json_f64).map(|v| query.bind(v)),
+        "NUMERIC" => convert(value, json_numeric).map(|v| query.bind(v)),
+        "UUID" => convert(value, json_uuid).map(|v| query.bind(v)),
+        "DATE" => convert(value, json_date).map(|v| query.bind(v)),
+        "TIME" => convert(value, json_time).map(|v| query.bind(v)),
+        "TIMETZ" => convert(value, json_timetz).map(|v| query.bind(v)),
+        "TIMESTAMP" => convert(value, json_timestamp).map(|v| query.bind(v)),
+        "TIMESTAMPTZ" => convert(value, json_timestamptz).map(|v| query.bind(v)),
+        "INET" | "CIDR" => convert(value, json_ip_network).map(|v| query.bind(v)),
+        "BYTEA" => convert(value, json_bytes).map(|v| query.bind(v)),
+        "JSON" | "JSONB" => Ok(query.bind((!value.is_null()).then(|| Json(value.clone())))),
+        // Sent in the same form as text
+        "TEXT" | "VARCHAR" | "CHAR" | "\"CHAR\"" | "NAME" | "UNKNOWN" | "citext" => {
+            convert(value, json_string).map(|v| query.bind(v))
+        }
+        _ if matches!(base.kind(), PgTypeKind::Enum(_)) => convert(value, json_string).map(|v| query.bind(v)),
+        "BOOL[]" => convert(value, |v| json_array(v, json_bool)).map(|v| query.bind(v)),
+        "INT2[]" => convert(value, |v| json_array(v, json_i16)).map(|v| query.bind(v)),
+        "INT4[]" => convert(value, |v| json_array(v, json_i32)).map(|v| query.bind(v)),
+        "INT8[]" => convert(value, |v| json_array(v, json_i64)).map(|v| query.bind(v)),
+        "FLOAT4[]" => convert(value, |v| json_array(v, json_f32)).map(|v| query.bind(v)),
+        "FLOAT8[]" => convert(value, |v| json_array(v, json_f64)).map(|v| query.bind(v)),
+        "NUMERIC[]" => convert(value, |v| json_array(v, json_numeric)).map(|v| query.bind(v)),
+        "UUID[]" => convert(value, |v| json_array(v, json_uuid)).map(|v| query.bind(v)),
+        "DATE[]" => convert(value, |v| json_array(v, json_date)).map(|v| query.bind(v)),
+        "TIMESTAMP[]" => convert(value, |v| json_array(v, json_timestamp)).map(|v| query.bind(v)),
+        "TIMESTAMPTZ[]" => convert(value, |v| json_array(v, json_timestamptz)).map(|v| query.bind(v)),
+        "TEXT[]" | "VARCHAR[]" | "CHAR[]" | "NAME[]" => {
+            convert(value, |v| json_array(v, json_string)).map(|v| query.bind(v))
+        }
+        _ => {
+            return Err(format!(
+                "Parameter ${} has unsupported type {}; cast it in the query, e.g. ${}::text",
+                index, type_name, index
+            ))
+        }
+    };
+    bound.map_err(|_| format!("Parameter ${} expects {}, got {}", index, type_name, value))
+}
+
+/// MySQL coerces parameters on the server, so values are bound as the JSON type they came in.
+pub fn bind_mysql_value<'q>(
+    query: Query<'q, MySql, MySqlArguments>,
+    value: &serde_json::Value,
+) -> Query<'q, MySql, MySqlArguments> {
+    match value {
+        serde_json::Value::Null => query.bind(None::<String>),
+        serde_json::Value::Bool(b) => query.bind(*b),
+        serde_json::Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => query.bind(i),
+            (None, Some(u)) => query.bind(u),
+            _ => query.bind(n.as_f64()),
+        },
+        serde_json::Value::String(s) => query.bind(s.clone()),
+        other => query.bind(Json(other.clone())),
+    }
+}
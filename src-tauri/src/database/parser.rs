@@ -1,114 +1,692 @@
-use sqlparser::ast::{ColumnOption, Statement, TableConstraint};
-use sqlparser::dialect::{GenericDialect, MySqlDialect, PostgreSqlDialect};
-use sqlparser::parser::Parser;
+use std::collections::HashMap;
 
-use crate::types::{Column, Schema, Table};
+use sqlparser::ast::{
+    AlterColumnOperation, AlterTableOperation, ColumnDef, ColumnOption, CommentDef, CommentObject,
+    CreateIndex, CreateTable, DataType, EnumMember, Expr, Ident, ObjectName, ObjectType, ReferentialAction, Statement,
+    TableConstraint,
+};
+use sqlparser::dialect::{Dialect, GenericDialect, MsSqlDialect, MySqlDialect, PostgreSqlDialect, SQLiteDialect};
+use sqlparser::parser::{Parser, ParserError};
+use sqlparser::tokenizer::Location;
 
-pub fn parse_sql_to_schema(sql: &str, dialect: &str) -> Result<Schema, String> {
-    let dialect_box: Box<dyn sqlparser::dialect::Dialect> = match dialect {
-        "mysql" | "mariadb" => Box::new(MySqlDialect {}),
-        "postgres" | "postgresql" => Box::new(PostgreSqlDialect {}),
-        _ => Box::new(GenericDialect {}),
+use crate::types::{Column, ForeignKey, Schema, SchemaWarning, SourceLocation, Table, TableKind};
+
+use super::script::{parse_error_location, split_statements, statement_spans, ScriptMap};
+
+/// Splits a possibly qualified name into (schema, table). Anything in front of the schema
+/// (e.g. a database) is ignored.
+fn split_object_name(name: &ObjectName) -> (Option<String>, String) {
+    let mut parts = name.0.iter().rev();
+    let table = parts.next().map(|i| i.value.clone()).unwrap_or_default();
+    (parts.next().map(|i| i.value.clone()), table)
+}
+
+// NO ACTION is the default and is left out, as introspection does
+fn referential_action(action: &Option<ReferentialAction>) -> Option<String> {
+    action
+        .filter(|a| *a != ReferentialAction::NoAction)
+        .map(|a| a.to_string())
+}
+
+fn foreign_key(
+    table_schema: Option<&String>,
+    name: &Option<Ident>,
+    columns: &[Ident],
+    foreign_table: &ObjectName,
+    referred_columns: &[Ident],
+    on_delete: &Option<ReferentialAction>,
+    on_update: &Option<ReferentialAction>,
+) -> ForeignKey {
+    let (target_schema, target_table) = split_object_name(foreign_table);
+    ForeignKey {
+        name: name.as_ref().map(|n| n.value.clone()),
+        columns: columns.iter().map(|c| c.value.clone()).collect(),
+        target_schema: target_schema.filter(|s| Some(s) != table_schema),
+        target_table,
+        target_columns: referred_columns.iter().map(|c| c.value.clone()).collect(),
+        on_delete: referential_action(on_delete),
+        on_update: referential_action(on_update),
+    }
+}
+
+// Inline ENUM('a', 'b') keeps its labels apart so the type stays a plain name
+fn column_type(data_type: &DataType) -> (String, Vec<String>) {
+    match data_type {
+        DataType::Enum(members, _) => (
+            "ENUM".to_string(),
+            members
+                .iter()
+                .map(|member| match member {
+                    EnumMember::Name(name) | EnumMember::NamedValue(name, _) => name.clone(),
+                })
+                .collect(),
+        ),
+        data_type => (data_type.to_string(), Vec::new()),
+    }
+}
+
+// Inline PRIMARY KEY and UNIQUE, and MySQL's inline COMMENT '...'
+fn apply_column_options<'a>(column: &mut Column, options: impl IntoIterator<Item = &'a ColumnOption>) {
+    for option in options {
+        match option {
+            ColumnOption::Unique { is_primary: true, .. } => column.is_primary_key = true,
+            ColumnOption::Unique { .. } => column.is_unique = true,
+            ColumnOption::Comment(text) => column.comment = Some(text.clone()),
+            _ => {}
+        }
+    }
+}
+
+/// A column as declared, before table constraints and foreign keys are applied.
+fn column_from_def(col_def: &ColumnDef) -> Column {
+    let (column_type, enum_values) = column_type(&col_def.data_type);
+    let mut column = Column {
+        name: col_def.name.value.clone(),
+        column_type,
+        is_primary_key: false,
+        is_foreign_key: false,
+        is_unique: false,
+        foreign_key_target_table: None,
+        foreign_key_target_column: None,
+        foreign_key_target_schema: None,
+        enum_values,
+        comment: None,
+        is_multivalued: false,
+        is_derived: false,
+        cardinality_source: None,
+        cardinality_target: None,
     };
+    apply_column_options(&mut column, col_def.options.iter().map(|o| &o.option));
+    column
+}
 
-    let ast = Parser::parse_sql(&*dialect_box, sql).map_err(|e| format!("SQL Parse Error: {}", e))?;
+/// The inline REFERENCES of a column definition.
+fn inline_foreign_keys(table_schema: Option<&String>, col_def: &ColumnDef) -> Vec<ForeignKey> {
+    col_def
+        .options
+        .iter()
+        .filter_map(|option| match &option.option {
+            ColumnOption::ForeignKey {
+                foreign_table,
+                referred_columns,
+                on_delete,
+                on_update,
+                ..
+            } => Some(foreign_key(
+                table_schema,
+                &option.name,
+                std::slice::from_ref(&col_def.name),
+                foreign_table,
+                referred_columns,
+                on_delete,
+                on_update,
+            )),
+            _ => None,
+        })
+        .collect()
+}
 
-    let mut tables: Vec<Table> = Vec::new();
+/// Points every column at the first foreign key it is part of.
+fn link_foreign_key_columns(table: &mut Table) {
+    for column in &mut table.columns {
+        let reference = table.foreign_keys.iter().find(|fk| fk.columns.contains(&column.name));
+        column.is_foreign_key = reference.is_some();
+        column.foreign_key_target_table = reference.map(|fk| fk.target_table.clone());
+        column.foreign_key_target_column = reference
+            .and_then(|fk| fk.target_column(&column.name))
+            .map(str::to_string);
+        column.foreign_key_target_schema = reference.and_then(|fk| fk.target_schema.clone());
+    }
+}
 
-    for statement in ast {
-        if let Statement::CreateTable(create_table) = statement {
-            let table_name = create_table.name.to_string();
-            let mut columns: Vec<Column> = Vec::new();
-            let mut pk_columns: Vec<String> = Vec::new();
-            let mut fk_map: std::collections::HashMap<String, (String, String)> =
-                std::collections::HashMap::new();
+fn column_mut<'a>(table: &'a mut Table, name: &str) -> Result<&'a mut Column, String> {
+    let table_name = &table.name;
+    table
+        .columns
+        .iter_mut()
+        .find(|c| c.name == name)
+        .ok_or_else(|| format!("Column {} not found in table {}", name, table_name))
+}
 
-            // First pass: collect constraints
-            for constraint in &create_table.constraints {
-                match constraint {
-                    TableConstraint::PrimaryKey { columns: pk_cols, .. } => {
-                        for col in pk_cols {
-                            pk_columns.push(col.value.clone());
-                        }
-                    }
-                    TableConstraint::ForeignKey {
-                        columns: fk_cols,
-                        foreign_table,
-                        referred_columns,
-                        ..
-                    } => {
-                        for (i, fk_col) in fk_cols.iter().enumerate() {
-                            let target_table = foreign_table.to_string();
-                            let target_col = referred_columns
-                                .get(i)
-                                .map(|c| c.value.clone())
-                                .unwrap_or_default();
-                            fk_map.insert(fk_col.value.clone(), (target_table, target_col));
-                        }
-                    }
-                    _ => {}
+/// Applies a table constraint from CREATE TABLE or ALTER TABLE ... ADD. Check constraints
+/// and composite unique constraints don't show in diagrams and are left out.
+fn apply_constraint(table: &mut Table, constraint: &TableConstraint) -> Result<(), String> {
+    match constraint {
+        TableConstraint::PrimaryKey { columns, .. } => {
+            for col in columns {
+                column_mut(table, &col.value)?.is_primary_key = true;
+            }
+        }
+        // Only a single-column constraint makes the column itself unique
+        TableConstraint::Unique { columns, .. } if columns.len() == 1 => {
+            column_mut(table, &columns[0].value)?.is_unique = true;
+        }
+        TableConstraint::ForeignKey {
+            name,
+            columns,
+            foreign_table,
+            referred_columns,
+            on_delete,
+            on_update,
+            ..
+        } => {
+            let fk = foreign_key(
+                table.schema.as_ref(),
+                name,
+                columns,
+                foreign_table,
+                referred_columns,
+                on_delete,
+                on_update,
+            );
+            table.foreign_keys.push(fk);
+            link_foreign_key_columns(table);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn table_from_create(create_table: &CreateTable) -> (Table, Vec<String>) {
+    let (schema, name) = split_object_name(&create_table.name);
+    let mut table = Table {
+        columns: create_table.columns.iter().map(column_from_def).collect(),
+        foreign_keys: create_table
+            .columns
+            .iter()
+            .flat_map(|col_def| inline_foreign_keys(schema.as_ref(), col_def))
+            .collect(),
+        name,
+        schema,
+        kind: TableKind::Table,
+        depends_on: Vec::new(),
+        comment: create_table.comment.as_ref().map(|comment| match comment {
+            CommentDef::WithEq(text) | CommentDef::WithoutEq(text) | CommentDef::AfterColumnDefsWithoutEq(text) => {
+                text.clone()
+            }
+        }),
+    };
+    link_foreign_key_columns(&mut table);
+
+    let warnings = create_table
+        .constraints
+        .iter()
+        .filter_map(|constraint| apply_constraint(&mut table, constraint).err())
+        .collect();
+    (table, warnings)
+}
+
+/// Tables as the statements replayed so far leave them.
+#[derive(Default)]
+struct Replay {
+    tables: Vec<Table>,
+    // Single-column unique indexes by name, so a later DROP INDEX can undo them:
+    // (schema, table, column)
+    unique_indexes: HashMap<String, (Option<String>, String, String)>,
+}
+
+impl Replay {
+    /// The latest table of this name. An unqualified name matches it in any schema.
+    fn position(&self, schema: Option<&str>, name: &str) -> Option<usize> {
+        self.tables
+            .iter()
+            .rposition(|t| t.name == name && schema.is_none_or(|s| t.schema.as_deref() == Some(s)))
+    }
+
+    fn find(&self, name: &ObjectName) -> Option<usize> {
+        let (schema, table) = split_object_name(name);
+        self.position(schema.as_deref(), &table)
+    }
+
+    /// Applies one statement, returning what couldn't be. Statements that don't change
+    /// tables (queries, DML, functions, ...) are passed over.
+    fn apply(&mut self, statement: &Statement) -> Vec<String> {
+        match statement {
+            Statement::CreateTable(create_table) => self.create_table(create_table),
+            Statement::AlterTable {
+                name,
+                if_exists,
+                operations,
+                ..
+            } => match self.find(name) {
+                Some(i) => operations
+                    .iter()
+                    .filter_map(|operation| self.alter_table(i, operation).err())
+                    .collect(),
+                None if *if_exists => Vec::new(),
+                None => vec![format!("Table {} not found", name)],
+            },
+            Statement::Drop {
+                object_type,
+                if_exists,
+                names,
+                ..
+            } => names
+                .iter()
+                .filter_map(|name| self.drop(object_type, name, *if_exists).err())
+                .collect(),
+            Statement::CreateIndex(create_index) => self.create_index(create_index).err().into_iter().collect(),
+            Statement::Comment {
+                object_type,
+                object_name,
+                comment,
+                ..
+            } => self
+                .comment(object_type, object_name, comment.clone())
+                .err()
+                .into_iter()
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn create_table(&mut self, create_table: &CreateTable) -> Vec<String> {
+        let existing = self.find(&create_table.name);
+        if existing.is_some() && create_table.if_not_exists {
+            return Vec::new();
+        }
+        let (table, mut warnings) = table_from_create(create_table);
+        match existing {
+            Some(i) => {
+                warnings.push(format!(
+                    "Table {} is created again; the new definition replaces the old one",
+                    table.name
+                ));
+                self.tables[i] = table;
+            }
+            None => self.tables.push(table),
+        }
+        warnings
+    }
+
+    fn alter_table(&mut self, i: usize, operation: &AlterTableOperation) -> Result<(), String> {
+        match operation {
+            AlterTableOperation::AddConstraint(constraint) => apply_constraint(&mut self.tables[i], constraint),
+            AlterTableOperation::AddColumn {
+                if_not_exists,
+                column_def,
+                ..
+            } => {
+                let table = &mut self.tables[i];
+                if table.columns.iter().any(|c| c.name == column_def.name.value) {
+                    return if *if_not_exists {
+                        Ok(())
+                    } else {
+                        Err(format!("Column {} already exists in table {}", column_def.name, table.name))
+                    };
+                }
+                let foreign_keys = inline_foreign_keys(table.schema.as_ref(), column_def);
+                table.columns.push(column_from_def(column_def));
+                table.foreign_keys.extend(foreign_keys);
+                link_foreign_key_columns(table);
+                Ok(())
+            }
+            AlterTableOperation::DropColumn {
+                column_name,
+                if_exists,
+                ..
+            } => {
+                let table = &mut self.tables[i];
+                let Some(position) = table.columns.iter().position(|c| c.name == column_name.value) else {
+                    return if *if_exists {
+                        Ok(())
+                    } else {
+                        Err(format!("Column {} not found in table {}", column_name, table.name))
+                    };
+                };
+                table.columns.remove(position);
+                table.foreign_keys.retain(|fk| !fk.columns.contains(&column_name.value));
+                link_foreign_key_columns(table);
+                Ok(())
+            }
+            AlterTableOperation::RenameColumn {
+                old_column_name,
+                new_column_name,
+            } => self.rename_column(i, &old_column_name.value, &new_column_name.value),
+            AlterTableOperation::RenameTable { table_name } => {
+                self.rename_table(i, table_name);
+                Ok(())
+            }
+            AlterTableOperation::DropConstraint { name, if_exists, .. } => {
+                let table = &mut self.tables[i];
+                let before = table.foreign_keys.len();
+                table.foreign_keys.retain(|fk| fk.name.as_deref() != Some(name.value.as_str()));
+                if table.foreign_keys.len() == before && !if_exists {
+                    // Primary key, unique and check constraint names aren't kept
+                    return Err(format!("Constraint {} on table {} is not a known foreign key", name, table.name));
+                }
+                link_foreign_key_columns(table);
+                Ok(())
+            }
+            AlterTableOperation::DropPrimaryKey => {
+                for column in &mut self.tables[i].columns {
+                    column.is_primary_key = false;
+                }
+                Ok(())
+            }
+            AlterTableOperation::AlterColumn { column_name, op } => {
+                // Nullability and defaults don't show in diagrams
+                if let AlterColumnOperation::SetDataType { data_type, .. } = op {
+                    let column = column_mut(&mut self.tables[i], &column_name.value)?;
+                    (column.column_type, column.enum_values) = column_type(data_type);
                 }
+                Ok(())
             }
+            // MySQL's MODIFY and CHANGE restate the whole column definition
+            AlterTableOperation::ModifyColumn {
+                col_name,
+                data_type,
+                options,
+                ..
+            } => {
+                let column = column_mut(&mut self.tables[i], &col_name.value)?;
+                (column.column_type, column.enum_values) = column_type(data_type);
+                column.comment = None;
+                apply_column_options(column, options);
+                Ok(())
+            }
+            AlterTableOperation::ChangeColumn {
+                old_name,
+                new_name,
+                data_type,
+                options,
+                ..
+            } => {
+                self.rename_column(i, &old_name.value, &new_name.value)?;
+                let column = column_mut(&mut self.tables[i], &new_name.value)?;
+                (column.column_type, column.enum_values) = column_type(data_type);
+                column.comment = None;
+                apply_column_options(column, options);
+                Ok(())
+            }
+            // Ownership, row level security, rules and triggers don't show in diagrams
+            AlterTableOperation::OwnerTo { .. }
+            | AlterTableOperation::EnableRowLevelSecurity
+            | AlterTableOperation::DisableRowLevelSecurity
+            | AlterTableOperation::EnableRule { .. }
+            | AlterTableOperation::EnableAlwaysRule { .. }
+            | AlterTableOperation::EnableReplicaRule { .. }
+            | AlterTableOperation::DisableRule { .. }
+            | AlterTableOperation::EnableTrigger { .. }
+            | AlterTableOperation::EnableAlwaysTrigger { .. }
+            | AlterTableOperation::EnableReplicaTrigger { .. }
+            | AlterTableOperation::DisableTrigger { .. } => Ok(()),
+            operation => Err(format!(
+                "Unsupported ALTER TABLE operation on {}: {}",
+                self.tables[i].name, operation
+            )),
+        }
+    }
 
-            // Second pass: process columns
-            for col_def in &create_table.columns {
-                let col_name = col_def.name.value.clone();
-                let col_type = col_def.data_type.to_string();
+    /// Renames a column along with the foreign keys on either end of it.
+    fn rename_column(&mut self, i: usize, old_name: &str, new_name: &str) -> Result<(), String> {
+        column_mut(&mut self.tables[i], old_name)?.name = new_name.to_string();
+        let (schema, name) = (self.tables[i].schema.clone(), self.tables[i].name.clone());
 
-                let mut is_pk = pk_columns.contains(&col_name);
-                let mut is_fk = fk_map.contains_key(&col_name);
-                let mut fk_target_table: Option<String> = None;
-                let mut fk_target_column: Option<String> = None;
+        let rename = |columns: &mut Vec<String>| {
+            for column in columns.iter_mut().filter(|c| *c == old_name) {
+                *column = new_name.to_string();
+            }
+        };
+        for fk in &mut self.tables[i].foreign_keys {
+            rename(&mut fk.columns);
+        }
+        for table in &mut self.tables {
+            let table_schema = table.schema.clone();
+            for fk in &mut table.foreign_keys {
+                if fk.target_table == name && fk.target_schema.as_ref().or(table_schema.as_ref()) == schema.as_ref() {
+                    rename(&mut fk.target_columns);
+                }
+            }
+            link_foreign_key_columns(table);
+        }
+        Ok(())
+    }
 
-                // Check inline constraints
-                for option in &col_def.options {
-                    match &option.option {
-                        ColumnOption::Unique { is_primary, .. } => {
-                            if *is_primary {
-                                is_pk = true;
-                            }
-                        }
-                        ColumnOption::ForeignKey {
-                            foreign_table,
-                            referred_columns,
-                            ..
-                        } => {
-                            is_fk = true;
-                            fk_target_table = Some(foreign_table.to_string());
-                            fk_target_column =
-                                referred_columns.first().map(|c| c.value.clone());
+    /// Renames a table and repoints the foreign keys that reference it.
+    fn rename_table(&mut self, i: usize, new_name: &ObjectName) {
+        let (new_schema, new_name) = split_object_name(new_name);
+        let table = &mut self.tables[i];
+        let old_schema = table.schema.clone();
+        let old_name = std::mem::replace(&mut table.name, new_name.clone());
+        if new_schema.is_some() {
+            table.schema = new_schema;
+        }
+        let schema = table.schema.clone();
+
+        for table in &mut self.tables {
+            let table_schema = table.schema.clone();
+            for fk in &mut table.foreign_keys {
+                let target_schema = fk.target_schema.as_ref().or(table_schema.as_ref());
+                if fk.target_table == old_name && target_schema == old_schema.as_ref() {
+                    fk.target_table = new_name.clone();
+                    fk.target_schema = schema.clone().filter(|s| Some(s) != table_schema.as_ref());
+                }
+            }
+            link_foreign_key_columns(table);
+        }
+    }
+
+    fn drop(&mut self, object_type: &ObjectType, name: &ObjectName, if_exists: bool) -> Result<(), String> {
+        match object_type {
+            ObjectType::Table => match self.find(name) {
+                Some(i) => {
+                    let dropped = self.tables.remove(i);
+                    // Foreign keys into the table go with it, as with CASCADE
+                    for table in &mut self.tables {
+                        let table_schema = table.schema.clone();
+                        table.foreign_keys.retain(|fk| {
+                            let target_schema = fk.target_schema.as_ref().or(table_schema.as_ref());
+                            fk.target_table != dropped.name || target_schema != dropped.schema.as_ref()
+                        });
+                        link_foreign_key_columns(table);
+                    }
+                    self.unique_indexes
+                        .retain(|_, (schema, table, _)| *table != dropped.name || *schema != dropped.schema);
+                    Ok(())
+                }
+                None if if_exists => Ok(()),
+                None => Err(format!("Table {} not found", name)),
+            },
+            ObjectType::Index => {
+                let (_, index) = split_object_name(name);
+                if let Some((schema, table, column)) = self.unique_indexes.remove(&index) {
+                    if let Some(i) = self.position(schema.as_deref(), &table) {
+                        if let Some(column) = self.tables[i].columns.iter_mut().find(|c| c.name == column) {
+                            column.is_unique = false;
                         }
-                        _ => {}
                     }
                 }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// A unique index on a single column marks the column unique; other indexes don't show
+    /// in diagrams.
+    fn create_index(&mut self, create_index: &CreateIndex) -> Result<(), String> {
+        let i = self
+            .find(&create_index.table_name)
+            .ok_or_else(|| format!("Table {} not found", create_index.table_name))?;
+        let [column] = create_index.columns.as_slice() else {
+            return Ok(());
+        };
+        let Expr::Identifier(column) = &column.expr else {
+            return Ok(());
+        };
+        if !create_index.unique {
+            return Ok(());
+        }
+
+        let table = &mut self.tables[i];
+        column_mut(table, &column.value)?.is_unique = true;
+        if let Some(name) = &create_index.name {
+            let (_, index) = split_object_name(name);
+            self.unique_indexes
+                .insert(index, (table.schema.clone(), table.name.clone(), column.value.clone()));
+        }
+        Ok(())
+    }
+
+    /// COMMENT ON TABLE / COLUMN. `IS NULL` clears the comment.
+    fn comment(&mut self, object_type: &CommentObject, name: &ObjectName, comment: Option<String>) -> Result<(), String> {
+        let mut parts: Vec<&str> = name.0.iter().map(|i| i.value.as_str()).collect();
+        let column = match object_type {
+            CommentObject::Table => None,
+            CommentObject::Column => parts.pop(),
+            _ => return Ok(()),
+        };
+        let table_name = parts.pop().unwrap_or_default();
+        let i = self
+            .position(parts.pop(), table_name)
+            .ok_or_else(|| format!("Table {} not found", table_name))?;
+        match column {
+            None => self.tables[i].comment = comment,
+            Some(column) => column_mut(&mut self.tables[i], column)?.comment = comment,
+        }
+        Ok(())
+    }
+}
+
+/// Dialects tried, in order, when a script's dialect is "auto".
+const AUTO_DIALECTS: [&str; 5] = ["mysql", "postgres", "sqlite", "mssql", "generic"];
+
+/// The dialect for a name, and the name it is reported under. Unknown names fall back to
+/// the generic dialect.
+fn dialect_for(name: &str) -> (&'static str, Box<dyn Dialect>) {
+    match name {
+        "mysql" | "mariadb" => ("mysql", Box::new(MySqlDialect {})),
+        "postgres" | "postgresql" => ("postgres", Box::new(PostgreSqlDialect {})),
+        "sqlite" => ("sqlite", Box::new(SQLiteDialect {})),
+        "mssql" | "sqlserver" => ("mssql", Box::new(MsSqlDialect {})),
+        _ => ("generic", Box::new(GenericDialect {})),
+    }
+}
+
+/// A statement with where it starts, or the warning it was skipped with.
+type ParsedStatement = Result<(Option<SourceLocation>, Statement), SchemaWarning>;
+
+/// Parses the whole script at once, failing on the first error.
+fn parse_strict(dialect: &dyn Dialect, map: &ScriptMap, sql: &str) -> Result<Vec<ParsedStatement>, String> {
+    let ast = Parser::parse_sql(dialect, sql).map_err(|e| e.to_string())?;
+    let spans = statement_spans(dialect, map).filter(|spans| spans.len() == ast.len());
+    Ok(ast
+        .into_iter()
+        .enumerate()
+        .map(|(i, statement)| Ok((spans.as_ref().map(|spans| map.location(spans[i].0)), statement)))
+        .collect())
+}
+
+/// Parses statement by statement, so the ones the parser can't handle (client commands,
+/// vendor syntax) are skipped with a warning instead of failing the script.
+fn parse_tolerant(dialect: &dyn Dialect, map: &ScriptMap) -> Vec<ParsedStatement> {
+    let mut statements = Vec::new();
+    for (start, end) in split_statements(dialect, map) {
+        let text = map.slice(start, end);
+        let location = map.location(start);
+        match Parser::parse_sql(dialect, &text) {
+            Ok(ast) => statements.extend(ast.into_iter().map(|statement| Ok((Some(location.clone()), statement)))),
+            Err(e) => {
+                let message = match e {
+                    ParserError::ParserError(message) | ParserError::TokenizerError(message) => message,
+                    e => e.to_string(),
+                };
+                // The parser counts lines from the start of the statement
+                let error_location = parse_error_location(&message)
+                    .and_then(|p| ScriptMap::new(&text).offset(Location::new(p.line as u64, p.column as u64)))
+                    .map_or(location, |offset| map.location(start + offset));
+                let message = message.rfind(" at Line: ").map_or(message.as_str(), |i| &message[..i]);
+                statements.push(Err(SchemaWarning {
+                    message: format!("Statement skipped: {}", message),
+                    location: Some(error_location),
+                }));
+            }
+        }
+    }
+    statements
+}
+
+fn parse_script(dialect: &str, map: &ScriptMap, sql: &str, tolerant: bool) -> Result<Vec<ParsedStatement>, String> {
+    let (_, dialect) = dialect_for(dialect);
+    if tolerant {
+        Ok(parse_tolerant(&*dialect, map))
+    } else {
+        parse_strict(&*dialect, map, sql)
+    }
+}
 
-                // Apply FK from table constraints
-                if let Some((target_table, target_col)) = fk_map.get(&col_name) {
-                    is_fk = true;
-                    fk_target_table = Some(target_table.clone());
-                    fk_target_column = Some(target_col.clone());
+/// Tries each of `AUTO_DIALECTS` in turn and keeps the first that skips the fewest
+/// statements, stopping at one that parses them all. If none parses the script (strict
+/// parsing), the error reported is the one that got furthest into it.
+fn detect_dialect(
+    map: &ScriptMap,
+    sql: &str,
+    tolerant: bool,
+) -> Result<(&'static str, Vec<ParsedStatement>), String> {
+    let skipped = |statements: &[ParsedStatement]| statements.iter().filter(|s| s.is_err()).count();
+    let mut best: Option<(&'static str, Vec<ParsedStatement>)> = None;
+    let mut furthest: Option<(Option<(usize, usize)>, String)> = None;
+    for name in AUTO_DIALECTS {
+        match parse_script(name, map, sql, tolerant) {
+            Ok(statements) => {
+                if best.as_ref().is_none_or(|(_, best)| skipped(&statements) < skipped(best)) {
+                    best = Some((name, statements));
+                }
+                if best.as_ref().is_some_and(|(_, best)| skipped(best) == 0) {
+                    break;
+                }
+            }
+            Err(message) => {
+                let reached = parse_error_location(&message).map(|l| (l.line, l.column));
+                if furthest.as_ref().is_none_or(|(best, _)| reached > *best) {
+                    furthest = Some((reached, format!("{} (as {})", message, name)));
                 }
+            }
+        }
+    }
+    best.ok_or_else(|| format!("SQL Parse Error: {}", furthest.unwrap_or_default().1))
+}
 
-                columns.push(Column {
-                    name: col_name,
-                    column_type: col_type,
-                    is_primary_key: is_pk,
-                    is_foreign_key: is_fk,
-                    foreign_key_target_table: fk_target_table,
-                    foreign_key_target_column: fk_target_column,
-                    is_multivalued: false,
-                    is_derived: false,
-                    cardinality_source: None,
-                    cardinality_target: None,
-                });
-            }
-
-            tables.push(Table {
-                name: table_name,
-                columns,
-            });
+pub struct ParsedSchema {
+    pub schema: Schema,
+    pub warnings: Vec<SchemaWarning>,
+    // The dialect the script was parsed with, as asked for or detected
+    pub dialect: &'static str,
+}
+
+/// Replays a script's DDL in order, so a migration history yields the schema it ends with.
+/// Statements that can't be applied (unknown tables or columns, unsupported ALTER TABLE
+/// operations) are skipped and reported as warnings, as are statements that fail to parse
+/// when `tolerant` is set. `dialect` may be "auto".
+pub fn parse_sql_to_schema(sql: &str, dialect: &str, tolerant: bool) -> Result<ParsedSchema, String> {
+    let map = ScriptMap::new(sql);
+    let (dialect, statements) = if dialect == "auto" {
+        detect_dialect(&map, sql, tolerant)?
+    } else {
+        let (name, _) = dialect_for(dialect);
+        let statements = parse_script(name, &map, sql, tolerant).map_err(|e| format!("SQL Parse Error: {}", e))?;
+        (name, statements)
+    };
+
+    let mut replay = Replay::default();
+    let mut warnings = Vec::new();
+    for statement in statements {
+        match statement {
+            // Warnings point at the start of their statement
+            Ok((location, statement)) => {
+                warnings.extend(replay.apply(&statement).into_iter().map(|message| SchemaWarning {
+                    message,
+                    location: location.clone(),
+                }))
+            }
+            Err(warning) => warnings.push(warning),
         }
     }
 
-    Ok(Schema { tables })
+    Ok(ParsedSchema {
+        schema: Schema { tables: replay.tables },
+        warnings,
+        dialect,
+    })
 }
@@ -0,0 +1,421 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use sqlx::mysql::{MySqlConnection, MySqlPool, MySqlPoolOptions};
+use sqlx::postgres::{PgConnection, PgPool, PgPoolOptions};
+use sqlx::{Connection, Row};
+use tokio::sync::{oneshot, Mutex, OwnedMutexGuard, RwLock};
+
+use crate::types::{ConnectionParams, ResultPage, SessionInfo};
+
+use super::connection::{build_connection_string, cancel_postgres_backend, kill_mysql_query};
+use super::digest::sha256_hex;
+use super::executor::{CursorHandle, PageRequest};
+
+const SESSION_POOL_SIZE: u32 = 4;
+
+const TRANSACTION_OPEN: &str =
+    "Connection settings changed while a transaction is open; commit or roll back before reconnecting";
+
+#[derive(Clone)]
+pub enum DbPool {
+    Postgres(PgPool),
+    MySql(MySqlPool),
+}
+
+impl DbPool {
+    pub async fn close(&self) {
+        match self {
+            DbPool::Postgres(pool) => pool.close().await,
+            DbPool::MySql(pool) => pool.close().await,
+        }
+    }
+}
+
+pub enum DbConnection {
+    Postgres(PgConnection),
+    MySql(MySqlConnection),
+}
+
+/// Maps the loose `dbType` strings used by the frontend onto the two engines we support.
+pub fn normalize_db_type(db_type: &str) -> Result<&'static str, String> {
+    match db_type {
+        "postgresql" | "postgres" => Ok("postgres"),
+        "mysql" | "mariadb" => Ok("mysql"),
+        _ => Err(format!("Unsupported database type: {}", db_type)),
+    }
+}
+
+pub async fn connect_postgres_pool(connection_string: &str, max_connections: u32) -> Result<PgPool, String> {
+    PgPoolOptions::new()
+        .max_connections(max_connections)
+        .connect(connection_string)
+        .await
+        .map_err(|e| format!("Connection failed: {}", e))
+}
+
+pub async fn connect_mysql_pool(connection_string: &str, max_connections: u32) -> Result<MySqlPool, String> {
+    MySqlPoolOptions::new()
+        .max_connections(max_connections)
+        .connect(connection_string)
+        .await
+        .map_err(|e| format!("Connection failed: {}", e))
+}
+
+/// A live connection to one server: a small pool for metadata queries plus one pinned
+/// connection that runs the user's SQL, so session state survives between runs.
+pub struct Session {
+    pub id: String,
+    pub db_type: &'static str,
+    pub database: Option<String>,
+    connection_string: String,
+    pub pool: DbPool,
+    pub connection: Arc<Mutex<DbConnection>>,
+    /// Server-side id of the pinned connection (Postgres backend pid / MySQL thread id).
+    pub backend_id: u64,
+    /// Scripts waiting for the pinned connection. An open cursor gives way to them.
+    pub queued_runs: AtomicUsize,
+    /// Whether the pinned connection has an open transaction, from our own BEGIN/COMMIT
+    /// commands and the transaction statements seen in executed scripts.
+    pub in_transaction: AtomicBool,
+    database_pools: Mutex<HashMap<String, PgPool>>,
+    connected_at: u64,
+}
+
+impl Session {
+    async fn open(id: String, params: &ConnectionParams) -> Result<Self, String> {
+        let db_type = normalize_db_type(&params.db_type)?;
+        let connection_string = build_connection_string(params);
+
+        let (pool, connection, backend_id) = match db_type {
+            "postgres" => {
+                let pool = connect_postgres_pool(&connection_string, SESSION_POOL_SIZE).await?;
+                let mut conn = PgConnection::connect(&connection_string)
+                    .await
+                    .map_err(|e| format!("Connection failed: {}", e))?;
+                let pid: i32 = sqlx::query("SELECT pg_backend_pid()")
+                    .fetch_one(&mut conn)
+                    .await
+                    .and_then(|row| row.try_get(0))
+                    .map_err(|e| format!("Failed to read backend pid: {}", e))?;
+                (DbPool::Postgres(pool), DbConnection::Postgres(conn), pid as u64)
+            }
+            _ => {
+                let pool = connect_mysql_pool(&connection_string, SESSION_POOL_SIZE).await?;
+                let mut conn = MySqlConnection::connect(&connection_string)
+                    .await
+                    .map_err(|e| format!("Connection failed: {}", e))?;
+                let thread_id: u64 = sqlx::query("SELECT CAST(CONNECTION_ID() AS UNSIGNED)")
+                    .fetch_one(&mut conn)
+                    .await
+                    .and_then(|row| row.try_get(0))
+                    .map_err(|e| format!("Failed to read connection id: {}", e))?;
+                (DbPool::MySql(pool), DbConnection::MySql(conn), thread_id)
+            }
+        };
+
+        let database = url::Url::parse(&connection_string)
+            .ok()
+            .map(|u| u.path().trim_start_matches('/').to_string())
+            .filter(|db| !db.is_empty());
+
+        Ok(Session {
+            id,
+            db_type,
+            database,
+            connection_string,
+            pool,
+            connection: Arc::new(Mutex::new(connection)),
+            backend_id,
+            queued_runs: AtomicUsize::new(0),
+            in_transaction: AtomicBool::new(false),
+            database_pools: Mutex::new(HashMap::new()),
+            connected_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+        })
+    }
+
+    /// Postgres can't switch databases on a live connection, so listing other databases
+    /// on the same server needs a pool per database. Those pools are cached here.
+    pub async fn postgres_database_pool(&self, database: &str) -> Result<PgPool, String> {
+        if let Some(pool) = self.database_pools.lock().await.get(database) {
+            return Ok(pool.clone());
+        }
+
+        let mut url = url::Url::parse(&self.connection_string)
+            .map_err(|e| format!("Invalid connection string: {}", e))?;
+        url.set_path(&format!("/{}", database));
+
+        // Connect without holding the lock so several databases can connect at once
+        let pool = connect_postgres_pool(url.as_str(), 1).await?;
+        let existing = {
+            let mut pools = self.database_pools.lock().await;
+            match pools.get(database) {
+                Some(existing) => Some(existing.clone()),
+                None => {
+                    pools.insert(database.to_string(), pool.clone());
+                    None
+                }
+            }
+        };
+        // Another caller connected to the same database in the meantime
+        if let Some(existing) = existing {
+            pool.close().await;
+            return Ok(existing);
+        }
+        Ok(pool)
+    }
+
+    /// A pool on `database`: the session's own when that's the database it connected to.
+    pub async fn postgres_pool_for(&self, database: &str) -> Result<PgPool, String> {
+        match &self.pool {
+            DbPool::Postgres(pool) if self.database.as_deref() == Some(database) => Ok(pool.clone()),
+            DbPool::Postgres(_) => self.postgres_database_pool(database).await,
+            DbPool::MySql(_) => Err("Not a PostgreSQL connection".to_string()),
+        }
+    }
+
+    pub fn in_transaction(&self) -> bool {
+        self.in_transaction.load(Ordering::SeqCst)
+    }
+
+    pub fn info(&self) -> SessionInfo {
+        SessionInfo {
+            id: self.id.clone(),
+            db_type: self.db_type.to_string(),
+            database: self.database.clone(),
+            connected_at: self.connected_at,
+            in_transaction: self.in_transaction(),
+        }
+    }
+
+    async fn close(&self) {
+        self.pool.close().await;
+        for pool in self.database_pools.lock().await.values() {
+            pool.close().await;
+        }
+        // The pinned connection is closed when the last reference to the session drops.
+    }
+}
+
+/// Queries running on pinned connections and the result sets they still hold open.
+/// Shared with the tasks that execute scripts, which outlive the command that started them.
+#[derive(Default)]
+pub struct QueryTracker {
+    running: Mutex<HashMap<String, Arc<Session>>>,
+    cursors: Mutex<HashMap<String, CursorHandle>>,
+    next_query_id: AtomicU64,
+}
+
+impl QueryTracker {
+    /// The caller's id for a query, or a generated one.
+    pub fn query_id(&self, query_id: Option<String>) -> String {
+        query_id
+            .filter(|id| !id.is_empty())
+            .unwrap_or_else(|| format!("q{}", self.next_query_id.fetch_add(1, Ordering::Relaxed) + 1))
+    }
+
+    /// Marks a query as running on the session's pinned connection. Call this while holding
+    /// the connection lock so a cancel can't land on somebody else's statement.
+    pub async fn begin_query(&self, query_id: Option<String>, session: &Arc<Session>) -> String {
+        let query_id = self.query_id(query_id);
+        self.running
+            .lock()
+            .await
+            .insert(query_id.clone(), session.clone());
+        query_id
+    }
+
+    pub async fn finish_query(&self, query_id: &str) {
+        self.running.lock().await.remove(query_id);
+    }
+
+    /// Asks the server to abort a running query and drops its open result set.
+    /// Returns false if the query had already finished and left no result set open.
+    pub async fn cancel_query(&self, query_id: &str) -> Result<bool, String> {
+        let closed_cursor = self.close_cursor(query_id).await;
+
+        // Hold the map while cancelling so the query can't finish and hand its connection
+        // to the next statement before the cancel reaches the server.
+        let running = self.running.lock().await;
+        let Some(session) = running.get(query_id) else {
+            return Ok(closed_cursor);
+        };
+
+        match &session.pool {
+            DbPool::Postgres(pool) => cancel_postgres_backend(pool, session.backend_id).await?,
+            DbPool::MySql(pool) => kill_mysql_query(pool, session.backend_id).await?,
+        }
+        Ok(true)
+    }
+
+    /// Waits for the session's pinned connection, closing any result set that holds it.
+    pub async fn acquire_connection(&self, session: &Session) -> OwnedMutexGuard<DbConnection> {
+        session.queued_runs.fetch_add(1, Ordering::SeqCst);
+        self.close_session_cursors(&session.id).await;
+        let conn = session.connection.clone().lock_owned().await;
+        session.queued_runs.fetch_sub(1, Ordering::SeqCst);
+        conn
+    }
+
+    /// Keeps a result set open for fetch_more. Refused when another script is already
+    /// waiting for the session's connection, since the cursor would hold it indefinitely.
+    pub async fn open_cursor(&self, query_id: &str, session: &Session, cursor: CursorHandle) -> bool {
+        let mut cursors = self.cursors.lock().await;
+        if session.queued_runs.load(Ordering::SeqCst) > 0 {
+            return false;
+        }
+        cursors.insert(query_id.to_string(), cursor);
+        true
+    }
+
+    /// Drops an open result set; the task serving it releases the connection.
+    pub async fn close_cursor(&self, query_id: &str) -> bool {
+        self.cursors.lock().await.remove(query_id).is_some()
+    }
+
+    pub async fn close_session_cursors(&self, session_id: &str) {
+        self.cursors
+            .lock()
+            .await
+            .retain(|_, cursor| cursor.session_id != session_id);
+    }
+
+    pub async fn fetch_more(&self, query_id: &str, n: usize) -> Result<ResultPage, String> {
+        let requests = self
+            .cursors
+            .lock()
+            .await
+            .get(query_id)
+            .map(|cursor| cursor.requests.clone())
+            .ok_or_else(|| format!("No open result set for query {}", query_id))?;
+
+        let (reply, page) = oneshot::channel();
+        requests
+            .send(PageRequest { n, reply })
+            .await
+            .map_err(|_| format!("No open result set for query {}", query_id))?;
+        page.await
+            .map_err(|_| format!("Result set for query {} was closed", query_id))?
+    }
+}
+
+/// Open sessions keyed by connection id, held in Tauri state.
+#[derive(Default)]
+pub struct ConnectionRegistry {
+    sessions: RwLock<HashMap<String, Arc<Session>>>,
+    queries: Arc<QueryTracker>,
+}
+
+impl ConnectionRegistry {
+    fn session_key(params: &ConnectionParams) -> String {
+        match &params.connection_id {
+            Some(id) if !id.is_empty() => id.clone(),
+            // The key goes out to the webview as the session id, so it mustn't carry the password
+            _ => format!("conn-{}", &sha256_hex(&build_connection_string(params))[..16]),
+        }
+    }
+
+    /// Returns the open session for these params, connecting first if needed. A session whose
+    /// connection details changed since it was opened is replaced.
+    pub async fn connect(&self, params: &ConnectionParams) -> Result<Arc<Session>, String> {
+        let key = Self::session_key(params);
+        let connection_string = build_connection_string(params);
+
+        if let Some(session) = self.sessions.read().await.get(&key) {
+            if session.connection_string == connection_string {
+                return Ok(session.clone());
+            }
+            // Replacing the session would silently roll the transaction back
+            if session.in_transaction() {
+                return Err(TRANSACTION_OPEN.to_string());
+            }
+        }
+
+        let session = Arc::new(Session::open(key.clone(), params).await?);
+
+        let mut sessions = self.sessions.write().await;
+        if let Some(existing) = sessions.get(&key) {
+            if existing.connection_string == connection_string {
+                // Someone else connected while we were handshaking; keep theirs.
+                let existing = existing.clone();
+                drop(sessions);
+                session.close().await;
+                return Ok(existing);
+            }
+            if existing.in_transaction() {
+                drop(sessions);
+                session.close().await;
+                return Err(TRANSACTION_OPEN.to_string());
+            }
+        }
+        let replaced = sessions.insert(key, session.clone());
+        drop(sessions);
+
+        if let Some(old) = replaced {
+            self.queries.close_session_cursors(&old.id).await;
+            old.close().await;
+        }
+
+        Ok(session)
+    }
+
+    pub async fn session(&self, id: &str) -> Result<Arc<Session>, String> {
+        self.sessions
+            .read()
+            .await
+            .get(id)
+            .cloned()
+            .ok_or_else(|| format!("No open session for connection {}", id))
+    }
+
+    /// An open session on the same server, as the same user, as `connection_string`, whichever
+    /// database either one names.
+    pub async fn session_on_server(&self, connection_string: &str) -> Option<Arc<Session>> {
+        let server = without_database(connection_string)?;
+        self.sessions
+            .read()
+            .await
+            .values()
+            .find(|s| without_database(&s.connection_string).as_ref() == Some(&server))
+            .cloned()
+    }
+
+    pub async fn disconnect(&self, id: &str) -> bool {
+        let removed = self.sessions.write().await.remove(id);
+        match removed {
+            Some(session) => {
+                self.queries.close_session_cursors(id).await;
+                session.close().await;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn queries(&self) -> Arc<QueryTracker> {
+        self.queries.clone()
+    }
+
+    pub async fn list(&self) -> Vec<SessionInfo> {
+        let mut sessions: Vec<SessionInfo> = self
+            .sessions
+            .read()
+            .await
+            .values()
+            .map(|s| s.info())
+            .collect();
+        sessions.sort_by_key(|s| s.connected_at);
+        sessions
+    }
+}
+
+fn without_database(connection_string: &str) -> Option<url::Url> {
+    let mut url = url::Url::parse(connection_string).ok()?;
+    url.set_path("");
+    Some(url)
+}
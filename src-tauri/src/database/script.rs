@@ -0,0 +1,227 @@
+use sqlparser::dialect::{Dialect, GenericDialect, MsSqlDialect, MySqlDialect, PostgreSqlDialect};
+use sqlparser::tokenizer::{Location, Token, Tokenizer};
+
+use crate::types::SourceLocation;
+
+/// Line/column bookkeeping for a script, so positions reported against one statement can be
+/// mapped back into the editor. Lines and columns are 1-based and count characters.
+pub struct ScriptMap {
+    script: String,
+    // Character offset at which each line starts
+    line_starts: Vec<usize>,
+}
+
+impl ScriptMap {
+    pub fn new(script: &str) -> Self {
+        let mut line_starts = vec![0];
+        for (i, ch) in script.chars().enumerate() {
+            if ch == '\n' {
+                line_starts.push(i + 1);
+            }
+        }
+        ScriptMap {
+            script: script.to_string(),
+            line_starts,
+        }
+    }
+
+    pub fn offset(&self, location: Location) -> Option<usize> {
+        let line_start = *self.line_starts.get((location.line as usize).checked_sub(1)?)?;
+        Some(line_start + (location.column as usize).checked_sub(1)?)
+    }
+
+    pub fn location(&self, offset: usize) -> SourceLocation {
+        let line = self.line_starts.partition_point(|&start| start <= offset);
+        SourceLocation {
+            line,
+            column: offset - self.line_starts[line - 1] + 1,
+        }
+    }
+
+    /// The script's text between two character offsets.
+    pub fn slice(&self, start: usize, end: usize) -> String {
+        self.script.chars().skip(start).take(end.saturating_sub(start)).collect()
+    }
+}
+
+/// Where each statement of a script sits, as character offsets `[start, end)` from its first
+/// token to its last, leaving out comments and the terminating semicolon. Empty statements
+/// are skipped, as `Parser::parse_sql` does.
+pub fn statement_spans(dialect: &dyn Dialect, map: &ScriptMap) -> Option<Vec<(usize, usize)>> {
+    let tokens = Tokenizer::new(dialect, &map.script).tokenize_with_location().ok()?;
+
+    let mut spans = Vec::new();
+    let mut current: Option<(Location, Location)> = None;
+    for token in tokens {
+        match token.token {
+            Token::SemiColon | Token::EOF => {
+                if let Some((start, end)) = current.take() {
+                    spans.push((map.offset(start)?, map.offset(end)?));
+                }
+            }
+            Token::Whitespace(_) => {}
+            _ => {
+                let start = current.map(|(start, _)| start).unwrap_or(token.span.start);
+                current = Some((start, token.span.end));
+            }
+        }
+    }
+    if let Some((start, end)) = current {
+        spans.push((map.offset(start)?, map.offset(end)?));
+    }
+    Some(spans)
+}
+
+/// Splits a script into statements without parsing it, so one statement the parser can't
+/// handle doesn't cost the rest. Spans are character offsets `[start, end)` from a
+/// statement's first character that isn't whitespace or a comment up to its delimiter.
+///
+/// Understands what dump tools emit around the SQL: psql meta-commands (`\connect`) and
+/// `COPY ... FROM stdin` data blocks, which are left out, the mysql client's `DELIMITER`,
+/// and SQL Server's `GO` batch separator.
+pub fn split_statements(dialect: &dyn Dialect, map: &ScriptMap) -> Vec<(usize, usize)> {
+    let chars: Vec<char> = map.script.chars().collect();
+    let backslash_escapes = dialect.supports_string_literal_backslash_escape();
+    let is_mysql = dialect.is::<MySqlDialect>();
+    let is_mssql = dialect.is::<MsSqlDialect>();
+    let dollar_quotes = dialect.is::<PostgreSqlDialect>() || dialect.is::<GenericDialect>();
+
+    let mut delimiter = vec![';'];
+    let mut spans = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut i = 0;
+    while i < chars.len() {
+        let line_end = |from: usize| chars[from..].iter().position(|&c| c == '\n').map_or(chars.len(), |n| from + n);
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        if c.is_whitespace() {
+            i += 1;
+        } else if (c == '-' && next == Some('-')) || (c == '#' && is_mysql) {
+            i = line_end(i);
+        } else if c == '/' && next == Some('*') {
+            i = find(&chars, i + 2, &['*', '/']).map_or(chars.len(), |end| end + 2);
+        } else if start.is_none() && c == '\\' {
+            i = line_end(i);
+        } else if start.is_none() && is_mysql && starts_with_keyword(&chars, i, "DELIMITER") {
+            let end = line_end(i);
+            let line: String = chars[i + "DELIMITER".len()..end].iter().collect();
+            if !line.trim().is_empty() {
+                delimiter = line.trim().chars().collect();
+            }
+            i = end;
+        } else if is_mssql && starts_with_keyword(&chars, i, "GO") && is_own_line(&chars, i, 2) {
+            if let Some(start) = start.take() {
+                spans.push((start, trim_end(&chars, start, i)));
+            }
+            i = line_end(i);
+        } else if chars[i..].starts_with(&delimiter) {
+            i += delimiter.len();
+            let Some(start) = start.take() else {
+                continue;
+            };
+            let end = trim_end(&chars, start, i - delimiter.len());
+            let statement: String = chars[start..end].iter().collect::<String>().to_uppercase();
+            let words: Vec<&str> = statement.split_whitespace().collect();
+            // The rows follow on the next line, up to a line holding only "\."
+            if words.first() == Some(&"COPY") && words.ends_with(&["FROM", "STDIN"]) {
+                i = line_end(i);
+                while i < chars.len() {
+                    let end = line_end(i + 1);
+                    let line: String = chars[i + 1..end].iter().collect();
+                    i = end;
+                    if line.trim() == "\\." {
+                        break;
+                    }
+                }
+            } else {
+                spans.push((start, end));
+            }
+        } else {
+            start.get_or_insert(i);
+            i = match c {
+                '\'' | '"' => skip_quoted(&chars, i, backslash_escapes),
+                '`' => skip_quoted(&chars, i, false),
+                '$' if dollar_quotes => dollar_quote_end(&chars, i).unwrap_or(i + 1),
+                _ => i + 1,
+            };
+        }
+    }
+    if let Some(start) = start {
+        spans.push((start, trim_end(&chars, start, chars.len())));
+    }
+    spans
+}
+
+fn find(chars: &[char], from: usize, needle: &[char]) -> Option<usize> {
+    (from..chars.len()).find(|&i| chars[i..].starts_with(needle))
+}
+
+fn trim_end(chars: &[char], start: usize, mut end: usize) -> usize {
+    while end > start && chars[end - 1].is_whitespace() {
+        end -= 1;
+    }
+    end
+}
+
+// `keyword` as a whole word, ignoring case
+fn starts_with_keyword(chars: &[char], i: usize, keyword: &str) -> bool {
+    let len = keyword.chars().count();
+    chars.len() >= i + len
+        && chars[i..i + len].iter().copied().map(|c| c.to_ascii_uppercase()).eq(keyword.chars())
+        && chars.get(i + len).is_none_or(|c| !c.is_alphanumeric() && *c != '_')
+}
+
+// Whether the `len` characters at `i` are alone on their line, apart from whitespace
+fn is_own_line(chars: &[char], i: usize, len: usize) -> bool {
+    let before = chars[..i].iter().rev().take_while(|&&c| c != '\n');
+    let after = chars[i + len..].iter().take_while(|&&c| c != '\n');
+    before.chain(after).all(|c| c.is_whitespace())
+}
+
+/// Index just past the quote closing the one at `i`. A doubled quote stands for itself.
+fn skip_quoted(chars: &[char], i: usize, backslash_escapes: bool) -> usize {
+    let quote = chars[i];
+    let mut j = i + 1;
+    while j < chars.len() {
+        let escaped = backslash_escapes && chars[j] == '\\';
+        if escaped || (chars[j] == quote && chars.get(j + 1) == Some(&quote)) {
+            j += 2;
+        } else if chars[j] == quote {
+            return j + 1;
+        } else {
+            j += 1;
+        }
+    }
+    chars.len()
+}
+
+/// Index just past a Postgres dollar-quoted string (`$$...$$`, `$tag$...$tag$`) starting at
+/// `i`, if one does. `$1` placeholders aren't tags, and a `$` inside a word (`a$b$c`) doesn't
+/// start one.
+fn dollar_quote_end(chars: &[char], i: usize) -> Option<usize> {
+    if i > 0 && (chars[i - 1].is_alphanumeric() || matches!(chars[i - 1], '_' | '$')) {
+        return None;
+    }
+    let tag_len = chars[i + 1..]
+        .iter()
+        .position(|c| !(c.is_alphanumeric() || *c == '_'))
+        .filter(|&len| len == 0 || !chars[i + 1].is_ascii_digit())?;
+    if chars.get(i + 1 + tag_len) != Some(&'$') {
+        return None;
+    }
+    let tag = &chars[i..i + tag_len + 2];
+    let end = find(chars, i + tag.len(), tag).map_or(chars.len(), |end| end + tag.len());
+    Some(end)
+}
+
+/// Pulls the " at Line: X, Column: Y" suffix out of a sqlparser error message.
+pub fn parse_error_location(message: &str) -> Option<SourceLocation> {
+    let rest = &message[message.rfind("Line: ")? + "Line: ".len()..];
+    let (line, rest) = rest.split_once(", Column: ")?;
+    let column: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+    Some(SourceLocation {
+        line: line.trim().parse().ok()?,
+        column: column.parse().ok()?,
+    })
+}
@@ -0,0 +1,245 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sqlx::mysql::MySqlPool;
+use sqlx::postgres::PgPool;
+use sqlx::Row;
+
+use crate::types::Schema;
+
+use super::digest::sha256_hex;
+use super::introspect::{
+    get_mysql_schema, get_postgres_schema, postgres_schema_condition, SchemaFilter, MYSQL_SYSTEM_DATABASES,
+};
+
+/// Bumped whenever introspection starts reading something new, so snapshots taken by an
+/// older build aren't reused.
+const SNAPSHOT_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    version: u32,
+    fingerprint: String,
+    schema: Schema,
+}
+
+/// Introspected schemas, one file per connection and schema filter under `schema-cache`
+/// in the app's data directory. A snapshot is only reused while the database's
+/// fingerprint still matches the one it was taken with.
+pub struct SchemaCache {
+    dir: Option<PathBuf>,
+}
+
+impl Default for SchemaCache {
+    fn default() -> Self {
+        let dir = dirs::data_local_dir().map(|dir| dir.join("sql-ide").join("schema-cache"));
+        SchemaCache::new(dir)
+    }
+}
+
+impl SchemaCache {
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        SchemaCache { dir }
+    }
+
+    // File names are digests so connection strings (and their passwords) stay out of them.
+    // The key is JSON and the digest SHA-256 so names hold across builds.
+    fn path(&self, key: serde_json::Value) -> Option<PathBuf> {
+        let digest = sha256_hex(&key.to_string());
+        Some(self.dir.as_ref()?.join(format!("{}.json", digest)))
+    }
+
+    fn load(&self, path: &Path, fingerprint: &str) -> Option<Schema> {
+        let content = std::fs::read_to_string(path).ok()?;
+        let snapshot: Snapshot = serde_json::from_str(&content).ok()?;
+        (snapshot.version == SNAPSHOT_VERSION && snapshot.fingerprint == fingerprint).then_some(snapshot.schema)
+    }
+
+    fn store(&self, path: &Path, fingerprint: String, schema: &Schema) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create schema cache directory: {}", e))?;
+        }
+        let snapshot = Snapshot {
+            version: SNAPSHOT_VERSION,
+            fingerprint,
+            schema: schema.clone(),
+        };
+        let content = serde_json::to_string(&snapshot).map_err(|e| format!("Failed to serialize schema snapshot: {}", e))?;
+        std::fs::write(path, content).map_err(|e| format!("Failed to write schema snapshot: {}", e))
+    }
+
+    /// The cached snapshot if the fingerprint matches, otherwise `introspect`'s result,
+    /// which then replaces it. Without a fingerprint the cache is bypassed.
+    async fn get_or_introspect<F>(
+        &self,
+        path: Option<PathBuf>,
+        fingerprint: Option<String>,
+        introspect: F,
+    ) -> Result<Schema, String>
+    where
+        F: std::future::Future<Output = Result<Schema, String>>,
+    {
+        let (Some(path), Some(fingerprint)) = (path, fingerprint) else {
+            return introspect.await;
+        };
+        if let Some(schema) = self.load(&path, &fingerprint) {
+            return Ok(schema);
+        }
+        let schema = introspect.await?;
+        // A snapshot that can't be written only costs the next diagram a fresh introspection
+        let _ = self.store(&path, fingerprint, &schema);
+        Ok(schema)
+    }
+
+    pub async fn postgres_schema(
+        &self,
+        pool: &PgPool,
+        connection_string: &str,
+        filter: &SchemaFilter,
+    ) -> Result<Schema, String> {
+        let path = self.path(serde_json::json!([
+            "postgres",
+            connection_string,
+            filter.include,
+            filter.exclude,
+            filter.table
+        ]));
+        let fingerprint = postgres_fingerprint(pool, filter).await.ok();
+        self.get_or_introspect(path, fingerprint, get_postgres_schema(pool, filter))
+            .await
+    }
+
+    pub async fn mysql_schema(
+        &self,
+        pool: &MySqlPool,
+        connection_string: &str,
+        database_name: &str,
+    ) -> Result<Schema, String> {
+        let path = self.path(serde_json::json!(["mysql", connection_string, database_name]));
+        let fingerprint = mysql_fingerprint(pool, database_name).await.ok();
+        self.get_or_introspect(path, fingerprint, get_mysql_schema(pool, database_name))
+            .await
+    }
+}
+
+/// A hash over the catalog rows a diagram is drawn from: relations, columns, constraints,
+/// indexes, enum labels, comments and view dependencies. Any DDL touching them changes it,
+/// while plain reads and writes don't. Catalogs shared by the whole database aren't
+/// narrowed to the filter, so DDL in another schema also counts as a change.
+async fn postgres_fingerprint(pool: &PgPool, filter: &SchemaFilter) -> Result<String, String> {
+    let query = format!(
+        r#"
+        SELECT md5(concat_ws('|',
+            (
+                SELECT string_agg(concat_ws(':', c.oid, n.nspname, c.relname, c.relkind), ',' ORDER BY c.oid)
+                FROM pg_class c
+                JOIN pg_namespace n ON n.oid = c.relnamespace
+                WHERE c.relkind IN ('r', 'p', 'v', 'm') AND {condition}
+            ),
+            (
+                SELECT string_agg(
+                    concat_ws(':', a.attrelid, a.attnum, a.attname, format_type(a.atttypid, a.atttypmod), a.attnotnull),
+                    ',' ORDER BY a.attrelid, a.attnum
+                )
+                FROM pg_attribute a
+                JOIN pg_class c ON c.oid = a.attrelid
+                JOIN pg_namespace n ON n.oid = c.relnamespace
+                WHERE c.relkind IN ('r', 'p', 'v', 'm') AND a.attnum > 0 AND NOT a.attisdropped AND {condition}
+            ),
+            (
+                SELECT string_agg(
+                    concat_ws(':', o.oid, o.conname, o.contype, o.conrelid, o.conkey, o.confrelid, o.confkey),
+                    ',' ORDER BY o.oid
+                )
+                FROM pg_constraint o
+                WHERE o.conrelid <> 0
+            ),
+            (
+                SELECT string_agg(
+                    -- A predicate or expressions change which columns count as unique
+                    concat_ws(
+                        ':', i.indexrelid, i.indrelid, i.indisunique, i.indkey,
+                        coalesce(md5(i.indpred::text), ''), coalesce(md5(i.indexprs::text), '')
+                    ),
+                    ',' ORDER BY i.indexrelid
+                )
+                FROM pg_index i
+            ),
+            (
+                SELECT string_agg(concat_ws(':', e.oid, e.enumlabel, e.enumsortorder), ',' ORDER BY e.oid)
+                FROM pg_enum e
+            ),
+            (
+                SELECT string_agg(concat_ws(':', d.objoid, d.objsubid, md5(d.description)), ',' ORDER BY d.objoid, d.objsubid)
+                FROM pg_description d
+                WHERE d.classoid = 'pg_class'::regclass
+            ),
+            (
+                SELECT string_agg(concat_ws(':', r.ev_class, d.refobjid), ',' ORDER BY r.ev_class, d.refobjid)
+                FROM pg_rewrite r
+                JOIN pg_depend d
+                    ON d.classid = 'pg_rewrite'::regclass
+                    AND d.objid = r.oid
+                    AND d.refclassid = 'pg_class'::regclass
+            )
+        )) AS fingerprint
+        "#,
+        condition = postgres_schema_condition("n.nspname")
+    );
+
+    let row = sqlx::query(&query)
+        .bind(filter.include.clone())
+        .bind(filter.exclude.clone())
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to fingerprint schema: {}", e))?;
+    Ok(row.get("fingerprint"))
+}
+
+/// Order-independent checksums over the data dictionary views a diagram is drawn from.
+/// TABLES.UPDATE_TIME and CREATE_TIME aren't used: they change with every write, or are
+/// cached for up to a day in MySQL 8.
+async fn mysql_fingerprint(pool: &MySqlPool, database_name: &str) -> Result<String, String> {
+    // (view, columns hashed per row); every view is narrowed by its TABLE_SCHEMA
+    let parts = [
+        ("TABLES", "TABLE_NAME, TABLE_TYPE, TABLE_COMMENT"),
+        (
+            "COLUMNS",
+            "TABLE_NAME, ORDINAL_POSITION, COLUMN_NAME, COLUMN_TYPE, IS_NULLABLE, COLUMN_KEY, COLUMN_COMMENT",
+        ),
+        (
+            "KEY_COLUMN_USAGE",
+            "TABLE_NAME, CONSTRAINT_NAME, ORDINAL_POSITION, COLUMN_NAME, REFERENCED_TABLE_SCHEMA, \
+             REFERENCED_TABLE_NAME, REFERENCED_COLUMN_NAME",
+        ),
+        ("STATISTICS", "TABLE_NAME, INDEX_NAME, SEQ_IN_INDEX, COLUMN_NAME, NON_UNIQUE"),
+        ("VIEWS", "TABLE_NAME, VIEW_DEFINITION"),
+    ];
+    let checksums: Vec<String> = parts
+        .iter()
+        .map(|(view, columns)| {
+            format!(
+                r#"(
+                    SELECT CONCAT(COUNT(*), ':', COALESCE(SUM(CRC32(CONCAT_WS(':', TABLE_SCHEMA, {}))), 0))
+                    FROM information_schema.{}
+                    WHERE (? IS NULL OR TABLE_SCHEMA = ?)
+                    AND TABLE_SCHEMA NOT IN {}
+                )"#,
+                columns, view, MYSQL_SYSTEM_DATABASES
+            )
+        })
+        .collect();
+    let query = format!("SELECT CONCAT_WS('|', {}) AS FINGERPRINT", checksums.join(", "));
+
+    // If no database specified, the fingerprint covers all user databases
+    let database = Some(database_name).filter(|db| !db.is_empty());
+    let mut statement = sqlx::query(&query);
+    for _ in &parts {
+        statement = statement.bind(database).bind(database);
+    }
+    let row = statement
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to fingerprint schema: {}", e))?;
+    Ok(row.get("FINGERPRINT"))
+}
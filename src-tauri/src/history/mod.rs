@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tokio::fs::OpenOptions;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+use crate::types::{HistoryEntry, QueryResponse};
+
+/// Oldest entries are dropped once the log grows past this.
+const MAX_ENTRIES: usize = 5000;
+
+/// Executed queries, kept in memory and appended to `history.jsonl` in the app's data
+/// directory so they survive restarts.
+pub struct QueryHistory {
+    path: Option<PathBuf>,
+    entries: Mutex<Option<Vec<HistoryEntry>>>,
+}
+
+impl Default for QueryHistory {
+    fn default() -> Self {
+        let path = dirs::data_local_dir().map(|dir| dir.join("sql-ide").join("history.jsonl"));
+        QueryHistory::new(path)
+    }
+}
+
+impl QueryHistory {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        QueryHistory {
+            path,
+            entries: Mutex::new(None),
+        }
+    }
+
+    async fn load(&self) -> Vec<HistoryEntry> {
+        let Some(path) = &self.path else {
+            return Vec::new();
+        };
+        let Ok(content) = tokio::fs::read_to_string(path).await else {
+            return Vec::new();
+        };
+        // A line cut short by a crash shouldn't cost the rest of the history
+        content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()
+    }
+
+    /// The entries, read from disk the first time they're needed.
+    async fn loaded<'a>(&self, entries: &'a mut Option<Vec<HistoryEntry>>) -> &'a mut Vec<HistoryEntry> {
+        if entries.is_none() {
+            *entries = Some(self.load().await);
+        }
+        entries.get_or_insert_with(Vec::new)
+    }
+
+    async fn append(&self, entry: &HistoryEntry) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("Failed to create history directory: {}", e))?;
+        }
+        let mut line = serde_json::to_string(entry).map_err(|e| format!("Failed to serialize history entry: {}", e))?;
+        line.push('\n');
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await
+            .map_err(|e| format!("Failed to open history file: {}", e))?;
+        file.write_all(line.as_bytes())
+            .await
+            .map_err(|e| format!("Failed to write history: {}", e))
+    }
+
+    async fn rewrite(&self, entries: &[HistoryEntry]) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let mut content = String::new();
+        for entry in entries {
+            let line = serde_json::to_string(entry).map_err(|e| format!("Failed to serialize history entry: {}", e))?;
+            content.push_str(&line);
+            content.push('\n');
+        }
+        tokio::fs::write(path, content)
+            .await
+            .map_err(|e| format!("Failed to write history: {}", e))
+    }
+
+    /// Records one execute_query call, successful or not.
+    pub async fn record(
+        &self,
+        connection_id: &str,
+        sql: &str,
+        parameters: &HashMap<String, serde_json::Value>,
+        outcome: &Result<QueryResponse, String>,
+        duration: u64,
+    ) -> Result<HistoryEntry, String> {
+        let mut guard = self.entries.lock().await;
+        let entries = self.loaded(&mut guard).await;
+
+        let (row_count, error) = match outcome {
+            Ok(response) => (
+                response.results.iter().map(|r| r.row_count).sum(),
+                response.results.iter().find_map(|r| r.error.clone()),
+            ),
+            Err(e) => (0, Some(e.clone())),
+        };
+
+        let entry = HistoryEntry {
+            id: entries.last().map(|e| e.id + 1).unwrap_or(1),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+            connection_id: connection_id.to_string(),
+            sql: sql.to_string(),
+            parameters: (!parameters.is_empty()).then(|| parameters.clone()),
+            duration,
+            row_count,
+            error,
+        };
+        entries.push(entry.clone());
+
+        if entries.len() > MAX_ENTRIES {
+            let excess = entries.len() - MAX_ENTRIES;
+            entries.drain(..excess);
+            self.rewrite(entries).await?;
+        } else {
+            self.append(&entry).await?;
+        }
+        Ok(entry)
+    }
+
+    /// Newest first. `text` matches anywhere in the SQL, ignoring case.
+    pub async fn search(&self, text: Option<&str>, connection_id: Option<&str>, limit: usize) -> Vec<HistoryEntry> {
+        let mut guard = self.entries.lock().await;
+        let entries = self.loaded(&mut guard).await;
+        let needle = text.map(|t| t.to_lowercase()).filter(|t| !t.is_empty());
+
+        entries
+            .iter()
+            .rev()
+            .filter(|e| connection_id.is_none_or(|id| e.connection_id == id))
+            .filter(|e| needle.as_ref().is_none_or(|n| e.sql.to_lowercase().contains(n)))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    pub async fn get(&self, id: u64) -> Option<HistoryEntry> {
+        let mut guard = self.entries.lock().await;
+        let entries = self.loaded(&mut guard).await;
+        entries.iter().find(|e| e.id == id).cloned()
+    }
+
+    /// Clears the history of one connection, or all of it. Returns how many entries went.
+    pub async fn clear(&self, connection_id: Option<&str>) -> Result<usize, String> {
+        let mut guard = self.entries.lock().await;
+        let entries = self.loaded(&mut guard).await;
+        let before = entries.len();
+        entries.retain(|e| connection_id.is_some_and(|id| e.connection_id != id));
+        self.rewrite(entries).await?;
+        Ok(before - entries.len())
+    }
+}
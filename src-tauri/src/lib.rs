@@ -2,12 +2,15 @@ mod types;
 mod database;
 mod mermaid;
 mod files;
+mod history;
 mod project_commands;
 
 use types::*;
 use database::*;
 use project_commands::*;
 use mermaid::generate_mermaid_code;
+use history::QueryHistory;
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::net::TcpStream;
 use std::{thread, time::Duration};
@@ -20,11 +23,17 @@ use std::fs;
 use std::io::{Read, Write};
 use tauri::path::BaseDirectory;
 use tauri::AppHandle;
+use tauri::Emitter;
 use tauri::Manager;
+use tauri::State;
 
 // Tauri Commands - ER Diagram Generation
 #[tauri::command]
-async fn generate_diagram(request: GenerateRequest) -> Result<GenerateResponse, String> {
+async fn generate_diagram(
+    registry: State<'_, ConnectionRegistry>,
+    schema_cache: State<'_, SchemaCache>,
+    request: GenerateRequest,
+) -> Result<GenerateResponse, String> {
     let style = request.style.as_deref().unwrap_or("chen");
     let config = request.config.unwrap_or(MermaidConfig {
         theme: Some("default".to_string()),
@@ -32,26 +41,65 @@ async fn generate_diagram(request: GenerateRequest) -> Result<GenerateResponse,
         randomize: Some(false),
         direction: Some("TD".to_string()),
         compact: Some(false),
+        show_enum_values: Some(false),
+        show_comments: Some(false),
     });
 
-    let schema = if request.db_type == "sql" {
+    let (schema, warnings, dialect) = if request.db_type == "sql" {
         let sql = request.sql.as_ref().ok_or("SQL code is required")?;
-        parse_sql_to_schema(sql, "postgres")?
+        let dialect = request.dialect.as_deref().unwrap_or("auto");
+        let parsed = parse_sql_to_schema(sql, dialect, request.tolerant.unwrap_or(true))?;
+        (parsed.schema, parsed.warnings, Some(parsed.dialect.to_string()))
     } else {
         let conn_string = request
             .connection_string
             .as_ref()
             .ok_or("Connection string is required")?;
 
+        // An open session on the same server lends its pools instead of connecting again
+        let session = registry.session_on_server(conn_string).await;
         match request.db_type.as_str() {
-            "postgres" => get_postgres_schema(conn_string).await?,
-            "mysql" | "mariadb" => get_mysql_schema(conn_string).await?,
+            "postgres" => {
+                let filter = SchemaFilter::new(request.include_schemas.as_ref(), request.exclude_schemas.as_ref());
+                let database_name = database_from_connection_string(conn_string)?;
+                let schema = match session.filter(|s| s.db_type == "postgres" && !database_name.is_empty()) {
+                    Some(session) => {
+                        let pool = session.postgres_pool_for(&database_name).await?;
+                        schema_cache.postgres_schema(&pool, conn_string, &filter).await
+                    }
+                    None => {
+                        let pool = connect_postgres_pool(conn_string, 1).await?;
+                        let schema = schema_cache.postgres_schema(&pool, conn_string, &filter).await;
+                        pool.close().await;
+                        schema
+                    }
+                };
+                (schema?, Vec::new(), None)
+            }
+            "mysql" | "mariadb" => {
+                let database_name = database_from_connection_string(conn_string)?;
+                let schema = match session.as_ref().map(|s| &s.pool) {
+                    Some(DbPool::MySql(pool)) => schema_cache.mysql_schema(pool, conn_string, &database_name).await,
+                    _ => {
+                        let pool = connect_mysql_pool(conn_string, 1).await?;
+                        let schema = schema_cache.mysql_schema(&pool, conn_string, &database_name).await;
+                        pool.close().await;
+                        schema
+                    }
+                };
+                (schema?, Vec::new(), None)
+            }
             _ => return Err(format!("Unsupported database type: {}", request.db_type)),
         }
     };
 
     let mermaid_code = generate_mermaid_code(&schema, style, &config);
-    Ok(GenerateResponse { mermaid_code, schema })
+    Ok(GenerateResponse {
+        mermaid_code,
+        schema,
+        warnings,
+        dialect,
+    })
 }
 
 #[tauri::command]
@@ -63,13 +111,16 @@ fn generate_mermaid(schema: Schema, style: Option<String>, config: Option<Mermai
         randomize: Some(false),
         direction: Some("TD".to_string()),
         compact: Some(false),
+        show_enum_values: Some(false),
+        show_comments: Some(false),
     });
     generate_mermaid_code(&schema, style, &config)
 }
 
 #[tauri::command]
 fn parse_sql(sql: String, dialect: String) -> Result<Schema, String> {
-    parse_sql_to_schema(&sql, &dialect)
+    // Without a way to report skipped statements, any that fail to parse fail the call
+    parse_sql_to_schema(&sql, &dialect, false).map(|parsed| parsed.schema)
 }
 
 // Tauri Commands - Database Connection
@@ -93,28 +144,369 @@ async fn test_connection_params(params: ConnectionParams) -> Result<String, Stri
 }
 
 #[tauri::command]
-async fn get_databases(params: ConnectionParams) -> Result<Vec<DatabaseInfo>, String> {
-    let connection_string = build_connection_string(&params);
-    match params.db_type.as_str() {
-        "postgresql" | "postgres" => get_postgres_databases(&connection_string).await,
-        "mysql" | "mariadb" => {
+async fn connect(registry: State<'_, ConnectionRegistry>, params: ConnectionParams) -> Result<SessionInfo, String> {
+    let session = registry.connect(&params).await?;
+    Ok(session.info())
+}
+
+#[tauri::command]
+async fn disconnect(registry: State<'_, ConnectionRegistry>, connection_id: String) -> Result<bool, String> {
+    Ok(registry.disconnect(&connection_id).await)
+}
+
+#[tauri::command]
+async fn list_sessions(registry: State<'_, ConnectionRegistry>) -> Result<Vec<SessionInfo>, String> {
+    Ok(registry.list().await)
+}
+
+#[tauri::command]
+async fn get_databases(registry: State<'_, ConnectionRegistry>, params: ConnectionParams) -> Result<Vec<DatabaseInfo>, String> {
+    let session = registry.connect(&params).await?;
+    match &session.pool {
+        DbPool::Postgres(_) => {
+            let filter = SchemaFilter::new(params.include_schemas.as_ref(), params.exclude_schemas.as_ref());
+            get_postgres_databases(&session, &filter).await
+        }
+        DbPool::MySql(pool) => {
             let db_name = params.database.as_deref().unwrap_or("");
-            get_mysql_databases(&connection_string, db_name).await
+            get_mysql_databases(pool, db_name).await
         }
-        _ => Err(format!("Unsupported database type: {}", params.db_type)),
     }
 }
 
+// Explorer tree, loaded one node at a time. A node that can't be read fails on its own
+// instead of taking its siblings down with it.
 #[tauri::command]
-async fn execute_query(params: ConnectionParams, query: String) -> Result<QueryResult, String> {
-    let connection_string = build_connection_string(&params);
-    match params.db_type.as_str() {
-        "postgresql" | "postgres" => execute_postgres_query(&connection_string, &query).await,
-        "mysql" | "mariadb" => execute_mysql_query(&connection_string, &query).await,
-        _ => Err(format!("Unsupported database type: {}", params.db_type)),
+async fn list_databases(registry: State<'_, ConnectionRegistry>, params: ConnectionParams) -> Result<Vec<DatabaseEntry>, String> {
+    let session = registry.connect(&params).await?;
+    match &session.pool {
+        DbPool::Postgres(_) => list_postgres_databases(&session).await,
+        DbPool::MySql(pool) => list_mysql_databases(pool, params.database.as_deref().unwrap_or("")).await,
+    }
+}
+
+#[tauri::command]
+async fn list_schemas(
+    registry: State<'_, ConnectionRegistry>,
+    params: ConnectionParams,
+    database: String,
+) -> Result<Vec<SchemaEntry>, String> {
+    let session = registry.connect(&params).await?;
+    match &session.pool {
+        DbPool::Postgres(_) => {
+            let filter = SchemaFilter::new(params.include_schemas.as_ref(), params.exclude_schemas.as_ref());
+            let pool = session.postgres_pool_for(&database).await?;
+            list_postgres_schemas(&pool, &filter).await
+        }
+        // A MySQL database is its own single schema
+        DbPool::MySql(_) => Ok(vec![SchemaEntry {
+            name: database,
+            error: None,
+        }]),
     }
 }
 
+#[tauri::command]
+async fn list_tables(
+    registry: State<'_, ConnectionRegistry>,
+    params: ConnectionParams,
+    database: String,
+    schema: String,
+) -> Result<Vec<TableEntry>, String> {
+    let session = registry.connect(&params).await?;
+    match &session.pool {
+        DbPool::Postgres(_) => {
+            let pool = session.postgres_pool_for(&database).await?;
+            list_postgres_tables(&pool, &schema).await
+        }
+        DbPool::MySql(pool) => list_mysql_tables(pool, &schema).await,
+    }
+}
+
+#[tauri::command]
+async fn describe_table(
+    registry: State<'_, ConnectionRegistry>,
+    params: ConnectionParams,
+    database: String,
+    schema: String,
+    table: String,
+) -> Result<TableInfo, String> {
+    let session = registry.connect(&params).await?;
+    match &session.pool {
+        DbPool::Postgres(_) => {
+            let pool = session.postgres_pool_for(&database).await?;
+            describe_postgres_table(&pool, &schema, &table).await
+        }
+        DbPool::MySql(pool) => describe_mysql_table(pool, &schema, &table).await,
+    }
+}
+
+#[tauri::command]
+async fn list_routines(
+    registry: State<'_, ConnectionRegistry>,
+    params: ConnectionParams,
+    database: String,
+    schema: String,
+) -> Result<Vec<RoutineInfo>, String> {
+    let session = registry.connect(&params).await?;
+    match &session.pool {
+        DbPool::Postgres(_) => {
+            let pool = session.postgres_pool_for(&database).await?;
+            list_postgres_routines(&pool, &schema).await
+        }
+        DbPool::MySql(pool) => list_mysql_routines(pool, &schema).await,
+    }
+}
+
+#[tauri::command]
+async fn list_triggers(
+    registry: State<'_, ConnectionRegistry>,
+    params: ConnectionParams,
+    database: String,
+    schema: String,
+) -> Result<Vec<TriggerInfo>, String> {
+    let session = registry.connect(&params).await?;
+    match &session.pool {
+        DbPool::Postgres(_) => {
+            let pool = session.postgres_pool_for(&database).await?;
+            list_postgres_triggers(&pool, &schema).await
+        }
+        DbPool::MySql(pool) => list_mysql_triggers(pool, &schema).await,
+    }
+}
+
+// Full CREATE statement of a routine or trigger. `kind` is "function", "procedure" or
+// "trigger"; Postgres tells overloaded routines apart by `arguments`, and triggers are
+// looked up on `table`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn get_definition(
+    registry: State<'_, ConnectionRegistry>,
+    params: ConnectionParams,
+    database: String,
+    schema: String,
+    kind: String,
+    name: String,
+    arguments: Option<String>,
+    table: Option<String>,
+) -> Result<String, String> {
+    let routine_kind = match kind.as_str() {
+        "function" => Some(RoutineKind::Function),
+        "procedure" => Some(RoutineKind::Procedure),
+        "trigger" => None,
+        _ => return Err(format!("Unsupported object kind: {}", kind)),
+    };
+    let session = registry.connect(&params).await?;
+    match (&session.pool, routine_kind) {
+        (DbPool::Postgres(_), Some(_)) => {
+            let pool = session.postgres_pool_for(&database).await?;
+            postgres_routine_definition(&pool, &schema, &name, arguments.as_deref().unwrap_or("")).await
+        }
+        (DbPool::Postgres(_), None) => {
+            let table = table.ok_or("Table is required for a trigger")?;
+            let pool = session.postgres_pool_for(&database).await?;
+            postgres_trigger_definition(&pool, &schema, &table, &name).await
+        }
+        (DbPool::MySql(pool), Some(routine_kind)) => mysql_routine_definition(pool, &schema, &name, routine_kind).await,
+        (DbPool::MySql(pool), None) => mysql_trigger_definition(pool, &schema, &name).await,
+    }
+}
+
+struct QueryRun {
+    params: ConnectionParams,
+    query: String,
+    parameters: HashMap<String, serde_json::Value>,
+    query_id: Option<String>,
+    page_size: Option<usize>,
+}
+
+async fn run_and_record(
+    app: AppHandle,
+    registry: &ConnectionRegistry,
+    history: &QueryHistory,
+    run: QueryRun,
+) -> Result<QueryResponse, String> {
+    let QueryRun {
+        params,
+        query,
+        parameters,
+        query_id,
+        page_size,
+    } = run;
+    let session = registry.connect(&params).await?;
+    let connection_id = session.id.clone();
+    // History is written to disk, so it only keeps the caller's connection id. Without one the
+    // session id stands in, which is a digest rather than the connection string.
+    let history_id = params
+        .connection_id
+        .clone()
+        .filter(|id| !id.is_empty())
+        .unwrap_or_else(|| connection_id.clone());
+    let start_time = std::time::Instant::now();
+    let outcome = execute_script(
+        registry.queries(),
+        session,
+        &query,
+        &parameters,
+        query_id,
+        page_size.unwrap_or(DEFAULT_PAGE_SIZE),
+        {
+            let connection_id = connection_id.clone();
+            move |query_id| {
+                let _ = app.emit(
+                    "query-started",
+                    QueryStarted {
+                        query_id: query_id.to_string(),
+                        connection_id,
+                    },
+                );
+            }
+        },
+    )
+    .await;
+
+    let duration = match &outcome {
+        Ok(response) => response.execution_time,
+        Err(_) => start_time.elapsed().as_millis() as u64,
+    };
+    // A history entry that can't be written shouldn't cost the user the query's results,
+    // but they should hear about it
+    match (history.record(&history_id, &query, &parameters, &outcome, duration).await, outcome) {
+        (Err(e), Ok(mut response)) => {
+            response.history_error = Some(e);
+            Ok(response)
+        }
+        (Err(e), Err(message)) => Err(format!("{}\n{}", message, e)),
+        (Ok(_), outcome) => outcome,
+    }
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn execute_query(
+    app: AppHandle,
+    registry: State<'_, ConnectionRegistry>,
+    history: State<'_, QueryHistory>,
+    params: ConnectionParams,
+    query: String,
+    parameters: Option<HashMap<String, serde_json::Value>>,
+    query_id: Option<String>,
+    page_size: Option<usize>,
+) -> Result<QueryResponse, String> {
+    let run = QueryRun {
+        params,
+        query,
+        parameters: parameters.unwrap_or_default(),
+        query_id,
+        page_size,
+    };
+    run_and_record(app, &registry, &history, run).await
+}
+
+#[tauri::command]
+async fn search_query_history(
+    history: State<'_, QueryHistory>,
+    search: Option<String>,
+    connection_id: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<HistoryEntry>, String> {
+    Ok(history
+        .search(search.as_deref(), connection_id.as_deref(), limit.unwrap_or(200))
+        .await)
+}
+
+/// Runs a history entry again, with the parameters it was run with, on the given connection.
+#[tauri::command]
+async fn rerun_query_history(
+    app: AppHandle,
+    registry: State<'_, ConnectionRegistry>,
+    history: State<'_, QueryHistory>,
+    params: ConnectionParams,
+    entry_id: u64,
+    query_id: Option<String>,
+    page_size: Option<usize>,
+) -> Result<QueryResponse, String> {
+    let entry = history
+        .get(entry_id)
+        .await
+        .ok_or_else(|| format!("History entry {} not found", entry_id))?;
+    let run = QueryRun {
+        params,
+        query: entry.sql,
+        parameters: entry.parameters.unwrap_or_default(),
+        query_id,
+        page_size,
+    };
+    run_and_record(app, &registry, &history, run).await
+}
+
+#[tauri::command]
+async fn clear_query_history(history: State<'_, QueryHistory>, connection_id: Option<String>) -> Result<usize, String> {
+    history.clear(connection_id.as_deref()).await
+}
+
+#[tauri::command]
+async fn get_query_parameters(db_type: String, query: String) -> Result<Vec<QueryParameter>, String> {
+    script_parameters(&db_type, &query)
+}
+
+#[tauri::command]
+async fn explain_query(
+    registry: State<'_, ConnectionRegistry>,
+    params: ConnectionParams,
+    query: String,
+    analyze: Option<bool>,
+    allow_writes: Option<bool>,
+    query_id: Option<String>,
+) -> Result<QueryPlan, String> {
+    let session = registry.connect(&params).await?;
+    explain_statement(
+        registry.queries(),
+        session,
+        &query,
+        analyze.unwrap_or(false),
+        allow_writes.unwrap_or(false),
+        query_id,
+    )
+    .await
+}
+
+#[tauri::command]
+async fn fetch_more(registry: State<'_, ConnectionRegistry>, query_id: String, n: usize) -> Result<ResultPage, String> {
+    registry.queries().fetch_more(&query_id, n).await
+}
+
+#[tauri::command]
+async fn close_cursor(registry: State<'_, ConnectionRegistry>, query_id: String) -> Result<bool, String> {
+    Ok(registry.queries().close_cursor(&query_id).await)
+}
+
+#[tauri::command]
+async fn begin_transaction(registry: State<'_, ConnectionRegistry>, connection_id: String) -> Result<SessionInfo, String> {
+    let session = registry.session(&connection_id).await?;
+    run_transaction_statement(&registry.queries(), &session, "BEGIN", true).await?;
+    Ok(session.info())
+}
+
+#[tauri::command]
+async fn commit(registry: State<'_, ConnectionRegistry>, connection_id: String) -> Result<SessionInfo, String> {
+    let session = registry.session(&connection_id).await?;
+    run_transaction_statement(&registry.queries(), &session, "COMMIT", false).await?;
+    Ok(session.info())
+}
+
+#[tauri::command]
+async fn rollback(registry: State<'_, ConnectionRegistry>, connection_id: String) -> Result<SessionInfo, String> {
+    let session = registry.session(&connection_id).await?;
+    run_transaction_statement(&registry.queries(), &session, "ROLLBACK", false).await?;
+    Ok(session.info())
+}
+
+#[tauri::command]
+async fn cancel_query(registry: State<'_, ConnectionRegistry>, query_id: String) -> Result<bool, String> {
+    registry.queries().cancel_query(&query_id).await
+}
+
 // Tauri Commands - File Operations
 #[tauri::command]
 async fn save_file(path: String, content: String) -> Result<(), String> {
@@ -167,13 +559,37 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
+        .manage(ConnectionRegistry::default())
+        .manage(QueryHistory::default())
+        .manage(SchemaCache::default())
         .invoke_handler(tauri::generate_handler![
             generate_diagram,
             generate_mermaid,
             test_connection,
             test_connection_params,
+            connect,
+            disconnect,
+            list_sessions,
             get_databases,
+            list_databases,
+            list_schemas,
+            list_tables,
+            describe_table,
+            list_routines,
+            list_triggers,
+            get_definition,
             execute_query,
+            get_query_parameters,
+            search_query_history,
+            rerun_query_history,
+            clear_query_history,
+            explain_query,
+            fetch_more,
+            close_cursor,
+            cancel_query,
+            begin_transaction,
+            commit,
+            rollback,
             parse_sql,
             save_file,
             read_file,
@@ -1,4 +1,6 @@
-use crate::types::{MermaidConfig, Schema};
+use std::collections::HashSet;
+
+use crate::types::{Column, ForeignKey, MermaidConfig, Schema, Table, TableKind, TableRef};
 
 fn sanitize_id(name: &str) -> String {
     name.chars()
@@ -6,6 +8,97 @@ fn sanitize_id(name: &str) -> String {
         .collect()
 }
 
+// Keeps `schema.table` distinct from a table literally named `schematable`
+fn table_id(label: &str) -> String {
+    sanitize_id(&label.replace('.', "__"))
+}
+
+/// Tables are labelled `schema.table` only when the diagram spans more than one schema,
+/// so single-schema diagrams keep their short names.
+fn spans_schemas(schema: &Schema) -> bool {
+    let mut schemas = schema.tables.iter().filter_map(|t| t.schema.as_deref());
+    match schemas.next() {
+        Some(first) => schemas.any(|s| s != first),
+        None => false,
+    }
+}
+
+fn table_label(schema: Option<&str>, name: &str, qualify: bool) -> String {
+    match schema {
+        Some(schema) if qualify => format!("{}.{}", schema, name),
+        _ => name.to_string(),
+    }
+}
+
+// Label of the table a foreign key points at; an unqualified target is in the same schema
+fn target_label(table: &Table, fk: &ForeignKey, qualify: bool) -> String {
+    let target_schema = fk.target_schema.as_deref().or(table.schema.as_deref());
+    table_label(target_schema, &fk.target_table, qualify)
+}
+
+/// The table's foreign keys, one per constraint. Schemas saved before constraints were
+/// recorded only carry per-column targets, so those get one single-column key each.
+fn table_foreign_keys(table: &Table) -> Vec<ForeignKey> {
+    if !table.foreign_keys.is_empty() {
+        return table.foreign_keys.clone();
+    }
+    table
+        .columns
+        .iter()
+        .filter(|col| col.is_foreign_key)
+        .filter_map(|col| {
+            Some(ForeignKey {
+                name: None,
+                columns: vec![col.name.clone()],
+                target_schema: col.foreign_key_target_schema.clone(),
+                target_table: col.foreign_key_target_table.clone()?,
+                target_columns: col.foreign_key_target_column.iter().cloned().collect(),
+                on_delete: None,
+                on_update: None,
+            })
+        })
+        .collect()
+}
+
+fn dependency_label(table: &Table, dependency: &TableRef, qualify: bool) -> String {
+    let schema = dependency.schema.as_deref().or(table.schema.as_deref());
+    table_label(schema, &dependency.name, qualify)
+}
+
+/// View dependencies as (view, dependency) labels. Dependencies that aren't part of the
+/// diagram (e.g. filtered-out schemas) are dropped rather than drawn as empty entities.
+fn view_dependencies(schema: &Schema, qualify: bool) -> Vec<(String, String)> {
+    let labels: HashSet<String> = schema
+        .tables
+        .iter()
+        .map(|t| table_label(t.schema.as_deref(), &t.name, qualify))
+        .collect();
+
+    let mut edges = Vec::new();
+    for table in &schema.tables {
+        let view = table_label(table.schema.as_deref(), &table.name, qualify);
+        for dependency in &table.depends_on {
+            let target = dependency_label(table, dependency, qualify);
+            if labels.contains(&target) {
+                edges.push((view.clone(), target));
+            }
+        }
+    }
+    edges
+}
+
+// Enum labels to show next to an attribute, when the diagram asks for them
+fn enum_label(col: &Column, show_enum_values: bool) -> Option<String> {
+    (show_enum_values && !col.enum_values.is_empty()).then(|| col.enum_values.join(" | ").replace('"', "'"))
+}
+
+// A table or column comment as one line of a quoted mermaid string, when the diagram asks for it
+fn comment_label(comment: Option<&str>, show_comments: bool) -> Option<String> {
+    comment
+        .filter(|c| show_comments && !c.trim().is_empty())
+        .map(|c| c.split_whitespace().collect::<Vec<_>>().join(" ").replace('"', "'"))
+}
+
 fn sanitize_name(name: &str) -> String {
     if name.chars().any(|c| !c.is_alphanumeric() && c != '_') {
         format!("\"{}\"", name)
@@ -43,12 +136,14 @@ fn get_theme_colors(theme: &str) -> (&'static str, &'static str, &'static str) {
     }
 }
 
-pub fn generate_crows_foot(schema: &Schema) -> String {
+pub fn generate_crows_foot(schema: &Schema, show_enum_values: bool, show_comments: bool) -> String {
     let mut code = String::from("erDiagram\n");
+    let qualify = spans_schemas(schema);
 
     // Generate Entities
     for table in &schema.tables {
-        code.push_str(&format!("    {} {{\n", sanitize_name(&table.name)));
+        let label = table_label(table.schema.as_deref(), &table.name, qualify);
+        code.push_str(&format!("    {} {{\n", sanitize_name(&label)));
         for col in &table.columns {
             let mut keys = Vec::new();
             if col.is_primary_key {
@@ -57,41 +152,73 @@ pub fn generate_crows_foot(schema: &Schema) -> String {
             if col.is_foreign_key {
                 keys.push("FK");
             }
+            if col.is_unique {
+                keys.push("UK");
+            }
             let key_string = if keys.is_empty() {
                 String::new()
             } else {
                 format!(" {}", keys.join(","))
             };
+            // The column comment and enum labels share the attribute's comment
+            let notes: Vec<String> = [
+                comment_label(col.comment.as_deref(), show_comments),
+                enum_label(col, show_enum_values),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+            let comment = if notes.is_empty() {
+                String::new()
+            } else {
+                format!(" \"{}\"", notes.join("; "))
+            };
             code.push_str(&format!(
-                "        {} {}{}\n",
+                "        {} {}{}{}\n",
                 sanitize_type(&col.column_type),
                 sanitize_name(&col.name),
-                key_string
+                key_string,
+                comment
             ));
         }
         code.push_str("    }\n");
     }
 
-    // Generate Relationships
+    // Generate Relationships, one per foreign key
     for table in &schema.tables {
-        for col in &table.columns {
-            if col.is_foreign_key {
-                if let Some(ref target_table) = col.foreign_key_target_table {
-                    let source = sanitize_name(&table.name);
-                    let target = sanitize_name(target_table);
-                    code.push_str(&format!(
-                        "    {} }}o--|| {} : \"{}\"\n",
-                        source, target, col.name
-                    ));
-                }
-            }
+        let source = sanitize_name(&table_label(table.schema.as_deref(), &table.name, qualify));
+        for fk in table_foreign_keys(table) {
+            let target = sanitize_name(&target_label(table, &fk, qualify));
+            code.push_str(&format!(
+                "    {} }}o--|| {} : \"{}\"\n",
+                source,
+                target,
+                fk.columns.join(",")
+            ));
         }
     }
 
+    // View dependencies, as dashed (non-identifying) relationships
+    for (view, target) in view_dependencies(schema, qualify) {
+        code.push_str(&format!(
+            "    {} }}o..o{{ {} : \"reads\"\n",
+            sanitize_name(&view),
+            sanitize_name(&target)
+        ));
+    }
+
     code
 }
 
-pub fn generate_chen(schema: &Schema, theme: &str, randomize: bool, direction: &str, compact: bool) -> String {
+pub fn generate_chen(
+    schema: &Schema,
+    theme: &str,
+    randomize: bool,
+    direction: &str,
+    compact: bool,
+    show_enum_values: bool,
+    show_comments: bool,
+) -> String {
     let mut code = format!("flowchart {}\n", direction);
 
     let (entity_color, attribute_color, relationship_color) = get_theme_colors(theme);
@@ -102,21 +229,35 @@ pub fn generate_chen(schema: &Schema, theme: &str, randomize: bool, direction: &
     code.push_str(&format!("    classDef derivedAttribute {},stroke-dasharray: 6 3;\n", attribute_color));
     code.push_str(&format!("    classDef multivaluedAttribute {},stroke-width: 3.5px;\n", attribute_color));
     code.push_str(&format!("    classDef relationship {};\n", relationship_color));
+    code.push_str(&format!("    classDef view {},stroke-dasharray: 6 3;\n", entity_color));
+
+    let qualify = spans_schemas(schema);
 
     for table in &schema.tables {
-        let entity_id = format!("E_{}", sanitize_id(&table.name));
+        let table_name = table_label(table.schema.as_deref(), &table.name, qualify);
+        let entity_id = format!("E_{}", table_id(&table_name));
+        let entity_class = if table.kind == TableKind::Table { "entity" } else { "view" };
         code.push_str(&format!(
-            "    {}[\"{}\"]:::entity\n",
-            entity_id, table.name
+            "    {}[\"{}\"]:::{}\n",
+            entity_id, table_name, entity_class
         ));
+        // The click only exists to carry the comment as a tooltip
+        if let Some(comment) = comment_label(table.comment.as_deref(), show_comments) {
+            code.push_str(&format!("    click {} callback \"{}\"\n", entity_id, comment));
+        }
 
         for col in &table.columns {
-            let attr_id = format!("A_{}_{}", sanitize_id(&table.name), sanitize_id(&col.name));
-            let label = if col.is_primary_key {
+            let attr_id = format!("A_{}_{}", table_id(&table_name), sanitize_id(&col.name));
+            let mut label = if col.is_primary_key {
                 format!("<u>{}</u>", col.name)
+            } else if col.is_unique {
+                format!("{} (U)", col.name)
             } else {
                 col.name.clone()
             };
+            if let Some(values) = enum_label(col, show_enum_values) {
+                label.push_str(&format!("<br/><small>{}</small>", values));
+            }
 
             let class_name = if col.is_derived {
                 "derivedAttribute"
@@ -129,7 +270,9 @@ pub fn generate_chen(schema: &Schema, theme: &str, randomize: bool, direction: &
             code.push_str(&format!("    {}([\"{}\"]):::{}\n", attr_id, label, class_name));
             // Add interaction only if security is loose.
             // We'll trust the frontend config to matching the click availability.
-            code.push_str(&format!("    click {} callback \"Edit Attribute\"\n", attr_id));
+            let tooltip = comment_label(col.comment.as_deref(), show_comments)
+                .unwrap_or_else(|| "Edit Attribute".to_string());
+            code.push_str(&format!("    click {} callback \"{}\"\n", attr_id, tooltip));
             
             // Distribute attributes around the entity by alternating direction if randomize is true
             let hash = col.name.len() + table.name.len(); 
@@ -159,7 +302,7 @@ pub fn generate_chen(schema: &Schema, theme: &str, randomize: bool, direction: &
         let cols = (n as f64).sqrt().ceil() as usize;
         
         let mut sorted_tables: Vec<String> = schema.tables.iter()
-            .map(|t| sanitize_id(&t.name))
+            .map(|t| table_id(&table_label(t.schema.as_deref(), &t.name, qualify)))
             .collect();
         // Sorting might help determinism, but keep original order might reflect DB better?
         // Let's keep schema order.
@@ -178,32 +321,51 @@ pub fn generate_chen(schema: &Schema, theme: &str, randomize: bool, direction: &
     // Relationships - Always Standard Direction logic (Source ---|N| Rel ---|1| Target)
     // We do NOT randomize this part.
     // let mut rel_counter = 0; // Removing counter in favor of deterministic IDs
+    let mut rel_ids = HashSet::new();
     for table in &schema.tables {
-        for col in &table.columns {
-            if col.is_foreign_key {
-                if let Some(ref target_table) = col.foreign_key_target_table {
-                    let source_id = format!("E_{}", sanitize_id(&table.name));
-                    let target_id = format!("E_{}", sanitize_id(target_table));
-                    // Deterministic ID: R_{SourceTable}_{ColumnName}
-                    let rel_id = format!("R_{}_{}", sanitize_id(&table.name), sanitize_id(&col.name));
-                    
-                    let source_card = col.cardinality_source.as_deref().unwrap_or("N");
-                    let target_card = col.cardinality_target.as_deref().unwrap_or("1");
-
-                    code.push_str(&format!(
-                        "    {}{{\"{}\"}}:::relationship\n",
-                        rel_id, col.name
-                    ));
-                    // Add click callback for the relationship
-                    code.push_str(&format!("    click {} callback \"Edit Relationship\"\n", rel_id));
-
-                    code.push_str(&format!("    {} ---|{}| {}\n", source_id, source_card, rel_id));
-                    code.push_str(&format!("    {} ---|{}| {}\n", rel_id, target_card, target_id));
-                }
+        let source = table_id(&table_label(table.schema.as_deref(), &table.name, qualify));
+        let source_id = format!("E_{}", source);
+        for fk in table_foreign_keys(table) {
+            let Some(first_column) = fk.columns.first() else {
+                continue;
+            };
+            let target_id = format!("E_{}", table_id(&target_label(table, &fk, qualify)));
+            // Deterministic ID: R_{SourceTable}_{FirstColumnName}, numbered when keys share a first column
+            let base_id = format!("R_{}_{}", source, sanitize_id(first_column));
+            let mut rel_id = base_id.clone();
+            let mut n = 1;
+            while !rel_ids.insert(rel_id.clone()) {
+                n += 1;
+                rel_id = format!("{}_{}", base_id, n);
             }
+
+            // Cardinality edits are stored on the key's first column
+            let col = table.columns.iter().find(|c| &c.name == first_column);
+            let source_card = col.and_then(|c| c.cardinality_source.as_deref()).unwrap_or("N");
+            let target_card = col.and_then(|c| c.cardinality_target.as_deref()).unwrap_or("1");
+
+            code.push_str(&format!(
+                "    {}{{\"{}\"}}:::relationship\n",
+                rel_id,
+                fk.columns.join(",")
+            ));
+            // Add click callback for the relationship
+            code.push_str(&format!("    click {} callback \"Edit Relationship\"\n", rel_id));
+
+            code.push_str(&format!("    {} ---|{}| {}\n", source_id, source_card, rel_id));
+            code.push_str(&format!("    {} ---|{}| {}\n", rel_id, target_card, target_id));
         }
     }
 
+    // View dependencies - dashed arrows from the view to what it reads
+    for (view, target) in view_dependencies(schema, qualify) {
+        code.push_str(&format!(
+            "    E_{} -.->|reads| E_{}\n",
+            table_id(&view),
+            table_id(&target)
+        ));
+    }
+
     code
 }
 
@@ -213,6 +375,8 @@ pub fn generate_mermaid_code(schema: &Schema, style: &str, config: &MermaidConfi
     let randomize = config.randomize.unwrap_or(false);
     let direction = config.direction.as_deref().unwrap_or("TD");
     let compact = config.compact.unwrap_or(false);
+    let show_enum_values = config.show_enum_values.unwrap_or(false);
+    let show_comments = config.show_comments.unwrap_or(false);
 
     let init_directive = if style == "chen" {
         format!(
@@ -224,9 +388,9 @@ pub fn generate_mermaid_code(schema: &Schema, style: &str, config: &MermaidConfi
     };
 
     let diagram_code = if style == "chen" {
-        generate_chen(schema, theme, randomize, direction, compact)
+        generate_chen(schema, theme, randomize, direction, compact, show_enum_values, show_comments)
     } else {
-        generate_crows_foot(schema)
+        generate_crows_foot(schema, show_enum_values, show_comments)
     };
 
     format!("{}{}", init_directive, diagram_code)
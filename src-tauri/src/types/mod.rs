@@ -10,10 +10,21 @@ pub struct Column {
     pub is_primary_key: bool,
     #[serde(rename = "isForeignKey")]
     pub is_foreign_key: bool,
+    // Covered by a single-column unique constraint or index
+    #[serde(rename = "isUnique", default)]
+    pub is_unique: bool,
     #[serde(rename = "foreignKeyTargetTable")]
     pub foreign_key_target_table: Option<String>,
     #[serde(rename = "foreignKeyTargetColumn")]
     pub foreign_key_target_column: Option<String>,
+    // None when the target lives in the same schema as the referencing table
+    #[serde(rename = "foreignKeyTargetSchema")]
+    pub foreign_key_target_schema: Option<String>,
+    // Labels of an enum-typed column, in declaration order
+    #[serde(rename = "enumValues", default)]
+    pub enum_values: Vec<String>,
+    #[serde(default)]
+    pub comment: Option<String>,
     #[serde(rename = "isMultivalued", default)]
     pub is_multivalued: bool,
     #[serde(rename = "isDerived", default)]
@@ -24,10 +35,61 @@ pub struct Column {
     pub cardinality_target: Option<String>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TableKind {
+    #[default]
+    Table,
+    View,
+    MaterializedView,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TableRef {
+    pub schema: Option<String>,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForeignKey {
+    pub name: Option<String>,
+    pub columns: Vec<String>,
+    // None when the target lives in the same schema as the referencing table
+    #[serde(rename = "targetSchema")]
+    pub target_schema: Option<String>,
+    #[serde(rename = "targetTable")]
+    pub target_table: String,
+    #[serde(rename = "targetColumns")]
+    pub target_columns: Vec<String>,
+    // CASCADE, SET NULL, ...; None when the constraint leaves it at the default
+    #[serde(rename = "onDelete")]
+    pub on_delete: Option<String>,
+    #[serde(rename = "onUpdate")]
+    pub on_update: Option<String>,
+}
+
+impl ForeignKey {
+    /// The column `column` references, if it is part of this key and the target is known.
+    pub fn target_column(&self, column: &str) -> Option<&str> {
+        let i = self.columns.iter().position(|c| c == column)?;
+        self.target_columns.get(i).map(String::as_str)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Table {
     pub name: String,
+    pub schema: Option<String>,
+    #[serde(default)]
+    pub kind: TableKind,
+    // Tables and views a view reads from
+    #[serde(rename = "dependsOn", default)]
+    pub depends_on: Vec<TableRef>,
     pub columns: Vec<Column>,
+    #[serde(rename = "foreignKeys", default)]
+    pub foreign_keys: Vec<ForeignKey>,
+    #[serde(default)]
+    pub comment: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -42,6 +104,12 @@ pub struct MermaidConfig {
     pub randomize: Option<bool>,
     pub direction: Option<String>,
     pub compact: Option<bool>,
+    // Lists enum labels next to enum-typed attributes
+    #[serde(rename = "showEnumValues")]
+    pub show_enum_values: Option<bool>,
+    // Adds table and column comments as tooltips (chen) or attribute comments (crow's foot)
+    #[serde(rename = "showComments")]
+    pub show_comments: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -53,6 +121,14 @@ pub struct GenerateRequest {
     pub sql: Option<String>,
     pub style: Option<String>,
     pub config: Option<MermaidConfig>,
+    #[serde(rename = "includeSchemas")]
+    pub include_schemas: Option<Vec<String>>,
+    #[serde(rename = "excludeSchemas")]
+    pub exclude_schemas: Option<Vec<String>>,
+    // Dialect of `sql`: mysql, postgres, sqlite, mssql, generic or auto (the default)
+    pub dialect: Option<String>,
+    // Skip statements of `sql` that fail to parse instead of failing (the default)
+    pub tolerant: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,6 +136,18 @@ pub struct GenerateResponse {
     #[serde(rename = "mermaidCode")]
     pub mermaid_code: String,
     pub schema: Schema,
+    // Statements of a SQL source that couldn't be applied to the schema
+    #[serde(default)]
+    pub warnings: Vec<SchemaWarning>,
+    // Dialect a SQL source was parsed with
+    pub dialect: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaWarning {
+    pub message: String,
+    // Start of the statement in the script
+    pub location: Option<SourceLocation>,
 }
 
 // SQL Editor Types
@@ -67,13 +155,191 @@ pub struct GenerateResponse {
 pub struct DatabaseInfo {
     pub name: String,
     pub tables: Vec<TableInfo>,
+    #[serde(default)]
+    pub enums: Vec<EnumType>,
+    #[serde(default)]
+    pub domains: Vec<DomainType>,
+    #[serde(default)]
+    pub sequences: Vec<SequenceInfo>,
+    #[serde(default)]
+    pub routines: Vec<RoutineInfo>,
+    #[serde(default)]
+    pub triggers: Vec<TriggerInfo>,
+    // Why the database couldn't be read, in which case everything else is empty
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnumType {
+    pub schema: Option<String>,
+    pub name: String,
+    pub labels: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DomainType {
+    pub schema: Option<String>,
+    pub name: String,
+    #[serde(rename = "baseType")]
+    pub base_type: String,
+    #[serde(rename = "notNull")]
+    pub not_null: bool,
+    #[serde(rename = "defaultValue")]
+    pub default_value: Option<String>,
+    pub constraints: Vec<CheckConstraint>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequenceInfo {
+    pub schema: Option<String>,
+    pub name: String,
+    #[serde(rename = "dataType")]
+    pub data_type: String,
+    #[serde(rename = "startValue")]
+    pub start_value: i64,
+    pub increment: i64,
+    // The column the sequence belongs to: serial, identity or OWNED BY
+    #[serde(rename = "ownedBy")]
+    pub owned_by: Option<SequenceOwner>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequenceOwner {
+    pub schema: Option<String>,
+    pub table: String,
+    pub column: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoutineKind {
+    Function,
+    Procedure,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutineInfo {
+    pub schema: Option<String>,
+    pub name: String,
+    pub kind: RoutineKind,
+    // Argument list as declared, e.g. "id integer, OUT total numeric". Together with the
+    // name it tells overloads apart.
+    pub arguments: String,
+    // None for procedures
+    #[serde(rename = "returnType")]
+    pub return_type: Option<String>,
+    pub language: String,
+    // Source between the AS quotes; None for C and internal functions, or when the user
+    // may not see it
+    pub body: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriggerInfo {
+    pub schema: Option<String>,
+    pub name: String,
+    pub table: String,
+    // BEFORE, AFTER or INSTEAD OF
+    pub timing: String,
+    // INSERT, UPDATE, DELETE, TRUNCATE
+    pub events: Vec<String>,
+    // ROW or STATEMENT
+    pub level: String,
+    // Postgres: the trigger function it executes
+    pub function: Option<String>,
+    // MySQL: the statement it executes
+    pub body: Option<String>,
+    pub enabled: bool,
+}
+
+// Explorer nodes listed before their contents are loaded. `error` is set when a node is
+// there but can't be opened, e.g. without CONNECT or USAGE privilege.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseEntry {
+    pub name: String,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaEntry {
+    pub name: String,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableEntry {
+    pub name: String,
+    pub schema: Option<String>,
+    pub kind: TableKind,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TableInfo {
     pub name: String,
     pub schema: Option<String>,
+    pub kind: TableKind,
+    // The SELECT behind a view or materialized view
+    pub definition: Option<String>,
+    #[serde(rename = "dependsOn")]
+    pub depends_on: Vec<TableRef>,
     pub columns: Vec<ColumnInfo>,
+    #[serde(rename = "foreignKeys")]
+    pub foreign_keys: Vec<ForeignKey>,
+    pub indexes: Vec<IndexInfo>,
+    #[serde(rename = "uniqueConstraints")]
+    pub unique_constraints: Vec<UniqueConstraint>,
+    #[serde(rename = "checkConstraints")]
+    pub check_constraints: Vec<CheckConstraint>,
+    pub comment: Option<String>,
+    // None for views
+    pub stats: Option<TableStats>,
+}
+
+// Planner estimates and on-disk sizes in bytes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableStats {
+    // None when the table has never been analyzed
+    #[serde(rename = "rowEstimate")]
+    pub row_estimate: Option<i64>,
+    #[serde(rename = "totalSize")]
+    pub total_size: i64,
+    #[serde(rename = "tableSize")]
+    pub table_size: i64,
+    #[serde(rename = "indexSize")]
+    pub index_size: i64,
+    // Milliseconds since the epoch, manual or automatic runs alike. Postgres only.
+    #[serde(rename = "lastVacuum")]
+    pub last_vacuum: Option<i64>,
+    #[serde(rename = "lastAnalyze")]
+    pub last_analyze: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexInfo {
+    pub name: String,
+    // Key columns in index order; expressions are given as their SQL text
+    pub columns: Vec<String>,
+    #[serde(rename = "isUnique")]
+    pub is_unique: bool,
+    #[serde(rename = "isPrimary")]
+    pub is_primary: bool,
+    // btree, hash, gin, ... (Postgres access method / MySQL index type)
+    pub method: Option<String>,
+    // WHERE clause of a partial index
+    pub predicate: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UniqueConstraint {
+    pub name: String,
+    pub columns: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckConstraint {
+    pub name: String,
+    pub expression: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -86,23 +352,175 @@ pub struct ColumnInfo {
     pub is_primary_key: bool,
     #[serde(rename = "isForeignKey")]
     pub is_foreign_key: bool,
+    #[serde(rename = "foreignKeyTargetTable")]
+    pub foreign_key_target_table: Option<String>,
+    #[serde(rename = "foreignKeyTargetColumn")]
+    pub foreign_key_target_column: Option<String>,
+    // None when the target lives in the same schema (database for MySQL)
+    #[serde(rename = "foreignKeyTargetSchema")]
+    pub foreign_key_target_schema: Option<String>,
+    #[serde(rename = "enumValues")]
+    pub enum_values: Vec<String>,
     #[serde(rename = "defaultValue")]
     pub default_value: Option<String>,
+    pub comment: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultColumn {
+    pub name: String,
+    #[serde(rename = "dbType")]
+    pub db_type: String,
+    pub nullable: Option<bool>,
+}
+
+// 1-based line and column in the executed script
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceLocation {
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatementError {
+    pub message: String,
+    #[serde(rename = "statementIndex")]
+    pub statement_index: usize,
+    // Range of the failing statement in the script
+    pub start: Option<SourceLocation>,
+    pub end: Option<SourceLocation>,
+    // SQLSTATE
+    pub code: Option<String>,
+    // MySQL/MariaDB error number
+    #[serde(rename = "errorNumber")]
+    pub error_number: Option<u16>,
+    pub detail: Option<String>,
+    pub hint: Option<String>,
+    // Where the server or parser located the error in the script
+    pub position: Option<SourceLocation>,
+}
+
+// Result of one statement in an executed script
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueryResult {
+    pub statement: String,
     pub columns: Vec<String>,
+    #[serde(rename = "columnTypes")]
+    pub column_types: Vec<ResultColumn>,
     pub rows: Vec<serde_json::Value>,
     #[serde(rename = "rowCount")]
     pub row_count: usize,
+    #[serde(rename = "rowsAffected")]
+    pub rows_affected: Option<u64>,
+    #[serde(rename = "executionTime")]
+    pub execution_time: u64,
+    pub error: Option<String>,
+    #[serde(rename = "errorDetails")]
+    pub error_details: Option<StatementError>,
+    // Rows were left on the server; only the first page was returned
+    pub truncated: bool,
+    // The remaining rows can still be read with fetch_more
+    #[serde(rename = "hasMore")]
+    pub has_more: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryResponse {
+    #[serde(rename = "queryId")]
+    pub query_id: String,
+    pub results: Vec<QueryResult>,
     #[serde(rename = "executionTime")]
     pub execution_time: u64,
+    #[serde(rename = "inTransaction")]
+    pub in_transaction: bool,
+    // The run couldn't be saved to the query history
+    #[serde(rename = "historyError", default)]
+    pub history_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultPage {
+    #[serde(rename = "queryId")]
+    pub query_id: String,
+    pub rows: Vec<serde_json::Value>,
+    #[serde(rename = "rowCount")]
+    pub row_count: usize,
+    #[serde(rename = "hasMore")]
+    pub has_more: bool,
+}
+
+// A placeholder found in a script: `:name`, `$1`, or `?` numbered as `?1`, `?2`, ...
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryParameter {
+    pub name: String,
+    // "named" or "positional"
+    pub kind: String,
+}
+
+// One node of an EXPLAIN plan, normalised across Postgres and MariaDB/MySQL
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanNode {
+    pub operation: String,
+    pub relation: Option<String>,
+    #[serde(rename = "startupCost")]
+    pub startup_cost: Option<f64>,
+    pub cost: Option<f64>,
+    // Estimated rows
+    pub rows: Option<f64>,
+    // Measured values, only present when the plan was analyzed
+    #[serde(rename = "actualRows")]
+    pub actual_rows: Option<f64>,
+    #[serde(rename = "actualTime")]
+    pub actual_time: Option<f64>,
+    pub loops: Option<f64>,
+    // Engine-specific fields that don't map onto the ones above (filters, index names, ...)
+    pub details: std::collections::HashMap<String, serde_json::Value>,
+    pub children: Vec<PlanNode>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryPlan {
+    #[serde(rename = "dbType")]
+    pub db_type: String,
+    pub analyzed: bool,
+    pub root: PlanNode,
+    #[serde(rename = "planningTime")]
+    pub planning_time: Option<f64>,
+    #[serde(rename = "executionTime")]
+    pub execution_time: Option<f64>,
+    // The plan exactly as the server returned it
+    pub raw: serde_json::Value,
+}
+
+// One execute_query call, as kept in the query history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub id: u64,
+    // Milliseconds since the Unix epoch
+    pub timestamp: u64,
+    #[serde(rename = "connectionId")]
+    pub connection_id: String,
+    pub sql: String,
+    pub parameters: Option<std::collections::HashMap<String, serde_json::Value>>,
+    // Milliseconds
+    pub duration: u64,
+    #[serde(rename = "rowCount")]
+    pub row_count: usize,
     pub error: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryStarted {
+    #[serde(rename = "queryId")]
+    pub query_id: String,
+    #[serde(rename = "connectionId")]
+    pub connection_id: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConnectionParams {
+    #[serde(rename = "connectionId")]
+    pub connection_id: Option<String>,
     #[serde(rename = "dbType")]
     pub db_type: String,
     pub host: Option<String>,
@@ -112,6 +530,23 @@ pub struct ConnectionParams {
     pub password: Option<String>,
     #[serde(rename = "connectionString")]
     pub connection_string: Option<String>,
+    // Postgres schemas to introspect; system schemas are always left out
+    #[serde(rename = "includeSchemas")]
+    pub include_schemas: Option<Vec<String>>,
+    #[serde(rename = "excludeSchemas")]
+    pub exclude_schemas: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionInfo {
+    pub id: String,
+    #[serde(rename = "dbType")]
+    pub db_type: String,
+    pub database: Option<String>,
+    #[serde(rename = "connectedAt")]
+    pub connected_at: u64,
+    #[serde(rename = "inTransaction")]
+    pub in_transaction: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -8,7 +8,15 @@ import MermaidDiagram from "./MermaidDiagram";
 import { Modal } from "./ui/Modal";
 import { CustomSelect } from "./ui/CustomSelect";
 import { useAppStore } from "../store/useAppStore";
-import type { Tab, SavedConnection, DiagramStyle, GenerateRequest } from "../types";
+import type {
+  Tab,
+  SavedConnection,
+  DiagramStyle,
+  GenerateRequest,
+  GenerateResponse,
+  SchemaWarning,
+  SqlDialect,
+} from "../types";
 
 const DB_TYPE_OPTIONS = [
   { value: "postgres", label: "PostgreSQL" },
@@ -16,6 +24,15 @@ const DB_TYPE_OPTIONS = [
   { value: "mariadb", label: "MariaDB" },
 ];
 
+const DIALECT_OPTIONS = [
+  { value: "auto", label: "Auto-detect" },
+  { value: "mysql", label: "MySQL / MariaDB" },
+  { value: "postgres", label: "PostgreSQL" },
+  { value: "sqlite", label: "SQLite" },
+  { value: "mssql", label: "SQL Server" },
+  { value: "generic", label: "Generic SQL" },
+];
+
 const THEME_OPTIONS = [
   { value: "default", label: "Default" },
   { value: "forest", label: "Forest" },
@@ -46,6 +63,10 @@ export default function ErGenerator() {
   const [testingConnection, setTestingConnection] = useState(false);
   const [mermaidCode, setMermaidCode] = useState<string>("");
   const [error, setError] = useState<string | null>(null);
+  const [warnings, setWarnings] = useState<SchemaWarning[]>([]);
+  const [sqlDialect, setSqlDialect] = useState<SqlDialect>("auto");
+  const [parsedDialect, setParsedDialect] = useState<SqlDialect | null>(null);
+  const [tolerant, setTolerant] = useState(true);
   const [connectionStatus, setConnectionStatus] = useState<string | null>(null);
   const [savedConnections, setSavedConnections] = useState<SavedConnection[]>([]);
   const [isSaveModalOpen, setIsSaveModalOpen] = useState(false);
@@ -301,6 +322,8 @@ export default function ErGenerator() {
 
     setLoading(true);
     setError(null);
+    setWarnings([]);
+    setParsedDialect(null);
     setMermaidCode("");
 
     try {
@@ -310,6 +333,8 @@ export default function ErGenerator() {
         type: activeTab === "database" ? data.dbType : "sql",
         connectionString: connectionString || undefined,
         sql: data.sql || undefined,
+        dialect: activeTab === "database" ? undefined : sqlDialect,
+        tolerant: activeTab === "database" ? undefined : tolerant,
         style: diagramStyle,
         config: {
           theme,
@@ -318,11 +343,13 @@ export default function ErGenerator() {
       };
 
       // Call Tauri command directly
-      const result = await invoke<{ mermaidCode: string }>("generate_diagram", {
+      const result = await invoke<GenerateResponse>("generate_diagram", {
         request: payload,
       });
 
       setMermaidCode(result.mermaidCode);
+      setWarnings(result.warnings ?? []);
+      setParsedDialect(result.dialect ?? null);
     } catch (err: any) {
       setError(err.toString());
     } finally {
@@ -655,6 +682,31 @@ CREATE TABLE posts (
                           className="w-full rounded-md border border-zinc-700 bg-zinc-900 px-3 py-2 text-sm text-zinc-100 placeholder-zinc-500 font-mono focus:outline-none focus:ring-2 focus:ring-blue-500"
                         />
                       </div>
+                      <div>
+                        <label className="block text-sm font-medium text-zinc-300 mb-1">
+                          Dialect
+                        </label>
+                        <CustomSelect
+                          value={sqlDialect}
+                          onChange={(value) => setSqlDialect(value as SqlDialect)}
+                          options={DIALECT_OPTIONS}
+                        />
+                        {sqlDialect === "auto" && parsedDialect && (
+                          <p className="mt-1 text-xs text-zinc-500">
+                            Parsed as{" "}
+                            {DIALECT_OPTIONS.find((option) => option.value === parsedDialect)?.label ?? parsedDialect}
+                          </p>
+                        )}
+                      </div>
+                      <label className="flex items-center gap-2 text-sm text-zinc-300">
+                        <input
+                          type="checkbox"
+                          checked={tolerant}
+                          onChange={(e) => setTolerant(e.target.checked)}
+                          className="rounded border-zinc-700 bg-zinc-900"
+                        />
+                        Skip statements that fail to parse (dumps, vendor syntax)
+                      </label>
                     </div>
                   )}
 
@@ -756,6 +808,21 @@ CREATE TABLE posts (
                       {error}
                     </div>
                   )}
+
+                  {warnings.length > 0 && (
+                    <div className="p-3 text-sm text-amber-400 bg-amber-900/20 border border-amber-800 rounded-md space-y-1">
+                      {warnings.map((warning, i) => (
+                        <div key={i}>
+                          {warning.location != null && (
+                            <span className="text-amber-600 font-mono mr-2">
+                              {warning.location.line}:{warning.location.column}
+                            </span>
+                          )}
+                          {warning.message}
+                        </div>
+                      ))}
+                    </div>
+                  )}
                 </form>
               )}
             </div>
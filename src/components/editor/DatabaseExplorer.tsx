@@ -1,4 +1,4 @@
-import { useState, useEffect, useRef } from "react";
+import { useState, useEffect, useRef, createContext, useContext } from "react";
 import {
   Database,
   Table,
@@ -9,10 +9,121 @@ import {
   RefreshCw,
   AlertCircle,
   GitBranch,
+  Eye,
+  ListTree,
+  FolderTree,
+  Code,
+  Zap,
 } from "lucide-react";
 import { useAppStore } from "../../store/useAppStore";
-import { getDatabases } from "../../services/database";
-import type { DatabaseInfo, DatabaseTable, DatabaseColumn } from "../../types";
+import {
+  listDatabases,
+  listSchemas,
+  listTables,
+  describeTable,
+  listRoutines,
+  listTriggers,
+  getRoutineDefinition,
+  getTriggerDefinition,
+} from "../../services/database";
+import type {
+  SavedConnection,
+  DatabaseEntry,
+  SchemaEntry,
+  TableEntry,
+  DatabaseTable,
+  DatabaseColumn,
+  DatabaseIndex,
+  TableStats,
+} from "../../types";
+
+// Loaded children by node id, so collapsing and re-expanding a node doesn't fetch it
+// again. The explorer starts a new cache on refresh.
+const NodeCacheContext = createContext<Map<string, unknown>>(new Map());
+
+// Children of a tree node, fetched when the node is first expanded (TreeNode only
+// mounts children while expanded). A failure stays on this node and can be retried.
+function useNodeChildren<T>(nodeId: string, load: () => Promise<T>, onLoad?: (data: T) => void) {
+  const cache = useContext(NodeCacheContext);
+  const [data, setData] = useState<T | undefined>(() => cache.get(nodeId) as T | undefined);
+  const [error, setError] = useState<string | null>(null);
+  const [isLoading, setIsLoading] = useState(() => !cache.has(nodeId));
+
+  const fetchChildren = async () => {
+    setIsLoading(true);
+    setError(null);
+    try {
+      const result = await load();
+      cache.set(nodeId, result);
+      setData(result);
+      onLoad?.(result);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setIsLoading(false);
+    }
+  };
+
+  useEffect(() => {
+    if (!cache.has(nodeId)) {
+      fetchChildren();
+    }
+  }, [nodeId, cache]);
+
+  return { data, error, isLoading, retry: fetchChildren };
+}
+
+interface StatusRowProps {
+  level: number;
+  isLoading?: boolean;
+  error?: string | null;
+  message?: string;
+  onRetry?: () => void;
+}
+
+// Placeholder row under a node: loading, failed to load, or empty
+function StatusRow({ level, isLoading, error, message, onRetry }: StatusRowProps) {
+  return (
+    <div
+      className="flex items-center gap-2 py-1 px-2"
+      style={{ paddingLeft: `${level * 12 + 8}px` }}
+      title={error ?? undefined}
+    >
+      <span className="w-4" />
+      {isLoading ? (
+        <RefreshCw className="w-3 h-3 text-zinc-600 animate-spin flex-shrink-0" />
+      ) : error ? (
+        <AlertCircle className="w-3 h-3 text-red-500 flex-shrink-0" />
+      ) : null}
+      <span className={`text-xs truncate ${error ? "text-red-400" : "text-zinc-600"}`}>
+        {isLoading ? "Loading..." : error ?? message}
+      </span>
+      {error && onRetry && (
+        <button
+          onClick={onRetry}
+          className="ml-auto text-xs text-blue-500 hover:text-blue-400 flex-shrink-0"
+        >
+          Retry
+        </button>
+      )}
+    </div>
+  );
+}
+
+// A listed table, until describeTable fills in the rest
+function tableFromEntry(entry: TableEntry): DatabaseTable {
+  return {
+    name: entry.name,
+    schema: entry.schema,
+    kind: entry.kind,
+    dependsOn: [],
+    columns: [],
+    foreignKeys: [],
+    indexes: [],
+    uniqueConstraints: [],
+    checkConstraints: [],
+  };
+}
 
 interface ContextMenuProps {
   x: number;
@@ -115,10 +226,19 @@ interface ColumnNodeProps {
 }
 
 function ColumnNode({ column, level }: ColumnNodeProps) {
+  const reference = column.foreignKeyTargetTable
+    ? `References ${column.foreignKeyTargetSchema ? `${column.foreignKeyTargetSchema}.` : ""}${
+        column.foreignKeyTargetTable
+      }(${column.foreignKeyTargetColumn ?? ""})`
+    : undefined;
+  const values = column.enumValues.length > 0 ? `Values: ${column.enumValues.join(", ")}` : undefined;
+  const title = [column.comment, reference, values].filter(Boolean).join("\n") || undefined;
+
   return (
     <div
       className="flex items-center gap-2 py-1 px-2 hover:bg-zinc-800 rounded cursor-pointer"
       style={{ paddingLeft: `${level * 12 + 8}px` }}
+      title={title}
     >
       <span className="w-4" />
       {column.isPrimaryKey ? (
@@ -134,43 +254,389 @@ function ColumnNode({ column, level }: ColumnNodeProps) {
   );
 }
 
-interface TableNodeProps {
-  table: DatabaseTable;
-  dbName: string;
+interface IndexNodeProps {
+  index: DatabaseIndex;
   level: number;
 }
 
-function TableNode({ table, dbName, level }: TableNodeProps) {
-  const nodeId = `${dbName}.${table.name}`;
+function IndexNode({ index, level }: IndexNodeProps) {
+  const details = [index.isPrimary ? "primary" : index.isUnique ? "unique" : null, index.method]
+    .filter(Boolean)
+    .join(", ");
 
   return (
-    <TreeNode
-      nodeId={nodeId}
-      label={table.name}
-      icon={<Table className="w-4 h-4 text-green-500" />}
-      level={level}
-      isExpandable={table.columns.length > 0}
+    <div
+      className="flex items-center gap-2 py-1 px-2 hover:bg-zinc-800 rounded cursor-pointer"
+      style={{ paddingLeft: `${level * 12 + 8}px` }}
+      title={index.predicate ? `WHERE ${index.predicate}` : undefined}
+    >
+      <span className="w-4" />
+      <ListTree className="w-3 h-3 text-purple-400 flex-shrink-0" />
+      <span className="text-sm text-zinc-400 truncate">
+        {index.name} ({index.columns.join(", ")})
+      </span>
+      <span className="text-xs text-zinc-600 truncate ml-auto">{details}</span>
+    </div>
+  );
+}
+
+function formatBytes(bytes: number): string {
+  const units = ["B", "KB", "MB", "GB", "TB"];
+  let value = bytes;
+  let unit = 0;
+  while (value >= 1024 && unit < units.length - 1) {
+    value /= 1024;
+    unit++;
+  }
+  return `${unit === 0 ? value : value.toFixed(1)} ${units[unit]}`;
+}
+
+function TableStatsRow({ stats, level }: { stats: TableStats; level: number }) {
+  const rows = stats.rowEstimate != null ? `~${stats.rowEstimate.toLocaleString()} rows` : "not analyzed";
+  const title = [
+    `Table: ${formatBytes(stats.tableSize)}`,
+    `Indexes: ${formatBytes(stats.indexSize)}`,
+    stats.lastVacuum && `Last vacuum: ${new Date(stats.lastVacuum).toLocaleString()}`,
+    stats.lastAnalyze && `Last analyze: ${new Date(stats.lastAnalyze).toLocaleString()}`,
+  ]
+    .filter(Boolean)
+    .join("\n");
+
+  return (
+    <div
+      className="flex items-center gap-2 py-1 px-2"
+      style={{ paddingLeft: `${level * 12 + 8}px` }}
+      title={title}
     >
+      <span className="w-4" />
+      <span className="text-xs text-zinc-600 truncate">
+        {rows}, {formatBytes(stats.totalSize)}
+      </span>
+    </div>
+  );
+}
+
+interface TableDetailsProps {
+  connection: SavedConnection;
+  nodeId: string;
+  dbName: string;
+  schema: string;
+  tableName: string;
+  level: number;
+}
+
+function TableDetails({ connection, nodeId, dbName, schema, tableName, level }: TableDetailsProps) {
+  const { setDatabaseTables } = useAppStore();
+  const { data: table, error, isLoading, retry } = useNodeChildren(
+    nodeId,
+    () => describeTable(connection, dbName, schema, tableName),
+    (table) => setDatabaseTables(dbName, [table])
+  );
+
+  if (!table) {
+    return <StatusRow level={level} isLoading={isLoading} error={error} onRetry={retry} />;
+  }
+
+  return (
+    <>
+      {table.stats && <TableStatsRow stats={table.stats} level={level} />}
       {table.columns.map((column) => (
         <ColumnNode
           key={`${nodeId}.${column.name}`}
           column={column}
-          level={level + 1}
+          level={level}
         />
       ))}
+      {table.indexes.map((index) => (
+        <IndexNode
+          key={`${nodeId}.index.${index.name}`}
+          index={index}
+          level={level}
+        />
+      ))}
+    </>
+  );
+}
+
+interface TableNodeProps {
+  connection: SavedConnection;
+  table: TableEntry;
+  dbName: string;
+  schema: string;
+  level: number;
+}
+
+function TableNode({ connection, table, dbName, schema, level }: TableNodeProps) {
+  const nodeId = `${dbName}.${schema}.${table.name}`;
+  const icon =
+    table.kind === "table" ? (
+      <Table className="w-4 h-4 text-green-500" />
+    ) : (
+      <Eye className="w-4 h-4 text-teal-500" />
+    );
+
+  return (
+    <TreeNode nodeId={nodeId} label={table.name} icon={icon} level={level} isExpandable>
+      <TableDetails
+        connection={connection}
+        nodeId={nodeId}
+        dbName={dbName}
+        schema={schema}
+        tableName={table.name}
+        level={level + 1}
+      />
     </TreeNode>
   );
 }
 
+interface TableListProps {
+  connection: SavedConnection;
+  dbName: string;
+  schema: string;
+  level: number;
+}
+
+function TableList({ connection, dbName, schema, level }: TableListProps) {
+  const { setDatabaseTables } = useAppStore();
+  const { data: tables, error, isLoading, retry } = useNodeChildren(
+    `${dbName}.${schema}`,
+    () => listTables(connection, dbName, schema),
+    (tables) => setDatabaseTables(dbName, tables.map(tableFromEntry))
+  );
+
+  if (!tables || tables.length === 0) {
+    return (
+      <StatusRow level={level} isLoading={isLoading} error={error} message="No tables" onRetry={retry} />
+    );
+  }
+
+  return (
+    <>
+      {tables.map((table) => (
+        <TableNode
+          key={`${dbName}.${schema}.${table.name}`}
+          connection={connection}
+          table={table}
+          dbName={dbName}
+          schema={schema}
+          level={level}
+        />
+      ))}
+    </>
+  );
+}
+
+interface DefinitionNodeProps {
+  label: string;
+  detail?: string;
+  title?: string;
+  icon: React.ReactNode;
+  level: number;
+  muted?: boolean;
+  loadDefinition: () => Promise<string>;
+}
+
+// A routine or trigger; clicking it opens its CREATE statement in a new tab
+function DefinitionNode({ label, detail, title, icon, level, muted, loadDefinition }: DefinitionNodeProps) {
+  const { addEditorTab } = useAppStore();
+  const [isLoading, setIsLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const handleClick = async () => {
+    setIsLoading(true);
+    setError(null);
+    try {
+      const definition = await loadDefinition();
+      addEditorTab({ name: label, type: "sql", content: definition, isDirty: false });
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setIsLoading(false);
+    }
+  };
+
+  return (
+    <div
+      className="flex items-center gap-2 py-1 px-2 hover:bg-zinc-800 rounded cursor-pointer"
+      style={{ paddingLeft: `${level * 12 + 8}px` }}
+      title={error ?? title}
+      onClick={handleClick}
+    >
+      <span className="w-4" />
+      {isLoading ? (
+        <RefreshCw className="w-3 h-3 text-zinc-600 animate-spin flex-shrink-0" />
+      ) : error ? (
+        <AlertCircle className="w-3 h-3 text-red-500 flex-shrink-0" />
+      ) : (
+        icon
+      )}
+      <span className={`text-sm truncate ${muted ? "text-zinc-600" : "text-zinc-400"}`}>{label}</span>
+      {detail && <span className="text-xs text-zinc-600 truncate ml-auto">{detail}</span>}
+    </div>
+  );
+}
+
+interface ObjectListProps {
+  connection: SavedConnection;
+  dbName: string;
+  schema: string;
+  level: number;
+}
+
+function RoutineList({ connection, dbName, schema, level }: ObjectListProps) {
+  const { data: routines, error, isLoading, retry } = useNodeChildren(`${dbName}.${schema}/routines`, () =>
+    listRoutines(connection, dbName, schema)
+  );
+
+  if (!routines || routines.length === 0) {
+    return (
+      <StatusRow level={level} isLoading={isLoading} error={error} message="No routines" onRetry={retry} />
+    );
+  }
+
+  return (
+    <>
+      {routines.map((routine) => (
+        <DefinitionNode
+          key={`${routine.name}(${routine.arguments})`}
+          label={`${routine.name}(${routine.arguments})`}
+          detail={routine.returnType ?? routine.kind}
+          title={routine.language}
+          icon={<Code className="w-3 h-3 text-sky-400 flex-shrink-0" />}
+          level={level}
+          loadDefinition={() => getRoutineDefinition(connection, dbName, routine)}
+        />
+      ))}
+    </>
+  );
+}
+
+function TriggerList({ connection, dbName, schema, level }: ObjectListProps) {
+  const { data: triggers, error, isLoading, retry } = useNodeChildren(`${dbName}.${schema}/triggers`, () =>
+    listTriggers(connection, dbName, schema)
+  );
+
+  if (!triggers || triggers.length === 0) {
+    return (
+      <StatusRow level={level} isLoading={isLoading} error={error} message="No triggers" onRetry={retry} />
+    );
+  }
+
+  return (
+    <>
+      {triggers.map((trigger) => (
+        <DefinitionNode
+          key={`${trigger.table}.${trigger.name}`}
+          label={trigger.name}
+          detail={`${trigger.timing} ${trigger.events.join(" OR ")} ON ${trigger.table}`}
+          title={trigger.function ?? trigger.body}
+          icon={<Zap className="w-3 h-3 text-orange-400 flex-shrink-0" />}
+          level={level}
+          muted={!trigger.enabled}
+          loadDefinition={() => getTriggerDefinition(connection, dbName, trigger)}
+        />
+      ))}
+    </>
+  );
+}
+
+// Tables, then routines and triggers in folders of their own
+function SchemaContents({ connection, dbName, schema, level }: ObjectListProps) {
+  return (
+    <>
+      <TableList connection={connection} dbName={dbName} schema={schema} level={level} />
+      <TreeNode
+        nodeId={`${dbName}.${schema}/routines`}
+        label="Routines"
+        icon={<Code className="w-4 h-4 text-sky-400" />}
+        level={level}
+        isExpandable
+      >
+        <RoutineList connection={connection} dbName={dbName} schema={schema} level={level + 1} />
+      </TreeNode>
+      <TreeNode
+        nodeId={`${dbName}.${schema}/triggers`}
+        label="Triggers"
+        icon={<Zap className="w-4 h-4 text-orange-400" />}
+        level={level}
+        isExpandable
+      >
+        <TriggerList connection={connection} dbName={dbName} schema={schema} level={level + 1} />
+      </TreeNode>
+    </>
+  );
+}
+
+interface SchemaNodeProps {
+  connection: SavedConnection;
+  schema: SchemaEntry;
+  dbName: string;
+  level: number;
+}
+
+function SchemaNode({ connection, schema, dbName, level }: SchemaNodeProps) {
+  return (
+    <TreeNode
+      nodeId={`${dbName}.${schema.name}`}
+      label={schema.name}
+      icon={<FolderTree className="w-4 h-4 text-amber-500" />}
+      level={level}
+      isExpandable
+    >
+      {schema.error ? (
+        <StatusRow level={level + 1} error={schema.error} />
+      ) : (
+        <SchemaContents connection={connection} dbName={dbName} schema={schema.name} level={level + 1} />
+      )}
+    </TreeNode>
+  );
+}
+
+interface SchemaListProps {
+  connection: SavedConnection;
+  dbName: string;
+  level: number;
+}
+
+function SchemaList({ connection, dbName, level }: SchemaListProps) {
+  const { data: schemas, error, isLoading, retry } = useNodeChildren(`${dbName}.`, () =>
+    listSchemas(connection, dbName)
+  );
+
+  if (!schemas || schemas.length === 0) {
+    return (
+      <StatusRow level={level} isLoading={isLoading} error={error} message="No schemas" onRetry={retry} />
+    );
+  }
+
+  // MySQL databases are their own single schema, so their tables sit right under them
+  if (schemas.length === 1 && schemas[0].name === dbName && !schemas[0].error) {
+    return <SchemaContents connection={connection} dbName={dbName} schema={dbName} level={level} />;
+  }
+
+  return (
+    <>
+      {schemas.map((schema) => (
+        <SchemaNode
+          key={`${dbName}.${schema.name}`}
+          connection={connection}
+          schema={schema}
+          dbName={dbName}
+          level={level}
+        />
+      ))}
+    </>
+  );
+}
+
 interface DatabaseNodeProps {
-  database: DatabaseInfo;
+  connection: SavedConnection;
+  database: DatabaseEntry;
   level: number;
   onContextMenu: (e: React.MouseEvent, databaseName: string) => void;
 }
 
-function DatabaseNode({ database, level, onContextMenu }: DatabaseNodeProps) {
-  const tables = database.tables || [];
-
+function DatabaseNode({ connection, database, level, onContextMenu }: DatabaseNodeProps) {
   const handleContextMenu = (e: React.MouseEvent) => {
     e.preventDefault();
     e.stopPropagation();
@@ -182,25 +648,24 @@ function DatabaseNode({ database, level, onContextMenu }: DatabaseNodeProps) {
       <TreeNode
         nodeId={database.name}
         label={database.name}
-        icon={<Database className="w-4 h-4 text-blue-500" />}
+        icon={<Database className={`w-4 h-4 ${database.error ? "text-zinc-600" : "text-blue-500"}`} />}
         level={level}
-        isExpandable={tables.length > 0}
+        isExpandable
       >
-        {tables.map((table) => (
-          <TableNode
-            key={`${database.name}.${table.name}`}
-            table={table}
-            dbName={database.name}
-            level={level + 1}
-          />
-        ))}
+        {database.error ? (
+          <StatusRow level={level + 1} error={database.error} />
+        ) : (
+          <SchemaList connection={connection} dbName={database.name} level={level + 1} />
+        )}
       </TreeNode>
     </div>
   );
 }
 
 export default function DatabaseExplorer() {
-  const { activeConnection, databases, setDatabases, openErDiagram } = useAppStore();
+  const { activeConnection, setDatabases, openErDiagram } = useAppStore();
+  const [databases, setDatabaseEntries] = useState<DatabaseEntry[]>([]);
+  const [nodeCache, setNodeCache] = useState(() => new Map<string, unknown>());
   const [isRefreshing, setIsRefreshing] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [contextMenu, setContextMenu] = useState<{ x: number; y: number; databaseName?: string } | null>(null);
@@ -217,12 +682,16 @@ export default function DatabaseExplorer() {
     
     setIsRefreshing(true);
     setError(null);
+    setNodeCache(new Map());
     
     try {
-      const dbs = await getDatabases(activeConnection);
-      setDatabases(dbs);
+      const dbs = await listDatabases(activeConnection);
+      setDatabaseEntries(dbs);
+      // Tables are filled in as their nodes are expanded
+      setDatabases(dbs.map((db) => ({ name: db.name, tables: [] })));
     } catch (err) {
       setError(err instanceof Error ? err.message : String(err));
+      setDatabaseEntries([]);
       setDatabases([]);
     } finally {
       setIsRefreshing(false);
@@ -294,7 +763,7 @@ export default function DatabaseExplorer() {
         ) : databases.length === 0 ? (
           <div className="px-4 py-8 text-center">
             <Database className="w-8 h-8 text-zinc-700 mx-auto mb-2" />
-            <p className="text-sm text-zinc-500">No tables found</p>
+            <p className="text-sm text-zinc-500">No databases found</p>
             <button
               onClick={handleRefresh}
               className="mt-2 text-sm text-blue-500 hover:text-blue-400"
@@ -303,9 +772,17 @@ export default function DatabaseExplorer() {
             </button>
           </div>
         ) : (
-          databases.map((db) => (
-            <DatabaseNode key={db.name} database={db} level={0} onContextMenu={handleContextMenu} />
-          ))
+          <NodeCacheContext.Provider value={nodeCache}>
+            {databases.map((db) => (
+              <DatabaseNode
+                key={db.name}
+                connection={activeConnection}
+                database={db}
+                level={0}
+                onContextMenu={handleContextMenu}
+              />
+            ))}
+          </NodeCacheContext.Provider>
         )}
       </div>
 
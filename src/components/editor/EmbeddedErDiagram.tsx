@@ -1,5 +1,5 @@
 import { useState, useEffect } from "react";
-import { Loader2, AlertCircle, RefreshCw, Layers, Spline, Shuffle, ArrowDown, ArrowRight, Grid } from "lucide-react";
+import { Loader2, AlertCircle, RefreshCw, Layers, Spline, Shuffle, ArrowDown, ArrowRight, Grid, List, MessageSquare } from "lucide-react";
 import { invoke } from "@tauri-apps/api/core";
 import { useAppStore } from "../../store/useAppStore";
 import MermaidDiagram from "../MermaidDiagram";
@@ -32,6 +32,8 @@ export default function EmbeddedErDiagram({ tab }: EmbeddedErDiagramProps) {
   const randomize = tab.diagramRandomize ?? diagramSettings.randomize ?? false;
   const direction = tab.diagramDirection || diagramSettings.direction || "TD";
   const compact = tab.diagramCompact ?? diagramSettings.compact ?? false;
+  const showEnumValues = tab.diagramEnumValues ?? diagramSettings.showEnumValues ?? false;
+  const showComments = tab.diagramComments ?? diagramSettings.showComments ?? false;
   const background = tab.diagramBackground || diagramSettings.background;
 
   const buildConnectionString = (conn: SavedConnection, dbName?: string): string => {
@@ -87,7 +89,11 @@ export default function EmbeddedErDiagram({ tab }: EmbeddedErDiagramProps) {
           randomize,
           direction,
           compact,
+          showEnumValues,
+          showComments,
         },
+        includeSchemas: connection.includeSchemas,
+        excludeSchemas: connection.excludeSchemas,
       };
 
       const result = await invoke<{ mermaidCode: string; schema: Schema }>("generate_diagram", {
@@ -111,7 +117,7 @@ export default function EmbeddedErDiagram({ tab }: EmbeddedErDiagramProps) {
       const newCode = await invoke<string>("generate_mermaid", {
         schema: newSchema,
         style,
-        config: { theme, curve, randomize, direction, compact },
+        config: { theme, curve, randomize, direction, compact, showEnumValues, showComments },
       });
       setMermaidCode(newCode);
       setSchema(newSchema);
@@ -176,7 +182,7 @@ export default function EmbeddedErDiagram({ tab }: EmbeddedErDiagramProps) {
     } else if (connection && mermaidCode) {
       generateDiagram();
     }
-  }, [style, theme, curve, randomize, direction, compact]);
+  }, [style, theme, curve, randomize, direction, compact, showEnumValues, showComments]);
 
   // Close context menu on global click
   useEffect(() => {
@@ -261,6 +267,20 @@ export default function EmbeddedErDiagram({ tab }: EmbeddedErDiagramProps) {
           >
             <Grid className="w-4 h-4" />
           </button>
+          <button
+            onClick={() => updateEditorTab(tab.id, { diagramEnumValues: !showEnumValues })}
+            className={`flex items-center gap-2 px-3 py-1.5 rounded text-sm font-medium transition-colors ${showEnumValues ? "bg-purple-600/20 text-purple-400" : "bg-zinc-800 text-zinc-400 hover:text-zinc-300"}`}
+            title="Show Enum Values"
+          >
+            <List className="w-4 h-4" />
+          </button>
+          <button
+            onClick={() => updateEditorTab(tab.id, { diagramComments: !showComments })}
+            className={`flex items-center gap-2 px-3 py-1.5 rounded text-sm font-medium transition-colors ${showComments ? "bg-purple-600/20 text-purple-400" : "bg-zinc-800 text-zinc-400 hover:text-zinc-300"}`}
+            title="Show Comments"
+          >
+            <MessageSquare className="w-4 h-4" />
+          </button>
           <button
             onClick={() => updateEditorTab(tab.id, { diagramRandomize: !randomize })}
             className={`flex items-center gap-2 px-3 py-1.5 rounded text-sm font-medium transition-colors ${randomize ? "bg-purple-600/20 text-purple-400" : "bg-zinc-800 text-zinc-400 hover:text-zinc-300"}`}
@@ -1,9 +1,13 @@
-import { useMemo, useCallback } from "react";
+import { useMemo, useCallback, useState } from "react";
 import { AgGridReact } from "ag-grid-react";
 import { AllCommunityModule, ModuleRegistry, themeQuartz } from "ag-grid-community";
 import type { ColDef, ValueFormatterParams } from "ag-grid-community";
 import { AlertCircle, CheckCircle, Clock, Table } from "lucide-react";
 import { useAppStore } from "../../../store/useAppStore";
+import { fetchMore } from "../../../services/database";
+import type { QueryResult, StatementResult } from "../../../types";
+
+const PAGE_SIZE = 1000;
 
 // Register AG Grid modules
 ModuleRegistry.registerModules([AllCommunityModule]);
@@ -20,12 +24,63 @@ const darkTheme = themeQuartz.withParams({
   cellTextColor: "#d4d4d8",
 });
 
+type ResultUpdate = Partial<Pick<StatementResult, "rows" | "rowCount" | "hasMore" | "truncated" | "error">>;
+
+// Short outcome of a statement for its tab
+function statementSummary(result: StatementResult): string {
+  if (result.error) return "failed";
+  if (result.columns.length > 0) {
+    return `${result.rowCount}${result.truncated ? "+" : ""} row${result.rowCount !== 1 ? "s" : ""}`;
+  }
+  if (result.rowsAffected != null) return `${result.rowsAffected} affected`;
+  return "";
+}
+
 export default function ResultsPanel() {
-  const { queryResults, isExecutingQuery } = useAppStore();
+  const { queryResults, isExecutingQuery, setQueryResults } = useAppStore();
+  const [isLoadingMore, setIsLoadingMore] = useState(false);
+
+  const statements = queryResults?.statements ?? [];
+  const selected = queryResults?.selectedStatement ?? 0;
+  // The statement on display, or the whole result when it isn't split by statement
+  const current: QueryResult | StatementResult | null = statements[selected] ?? queryResults;
+
+  const updateCurrent = useCallback(
+    (update: ResultUpdate) => {
+      if (!queryResults) return;
+      if (queryResults.statements?.[selected]) {
+        const updated = [...queryResults.statements];
+        updated[selected] = { ...updated[selected], ...update };
+        setQueryResults({ ...queryResults, statements: updated });
+      } else {
+        setQueryResults({ ...queryResults, ...update });
+      }
+    },
+    [queryResults, selected, setQueryResults]
+  );
+
+  const handleLoadMore = useCallback(async () => {
+    if (!queryResults?.queryId || !current?.hasMore) return;
+    setIsLoadingMore(true);
+    try {
+      const page = await fetchMore(queryResults.queryId, PAGE_SIZE);
+      const rows = [...current.rows, ...page.rows];
+      updateCurrent({
+        rows,
+        rowCount: rows.length,
+        hasMore: page.hasMore,
+        truncated: page.hasMore,
+      });
+    } catch (error) {
+      updateCurrent({ hasMore: false, error: String(error) });
+    } finally {
+      setIsLoadingMore(false);
+    }
+  }, [queryResults?.queryId, current, updateCurrent]);
 
   // Generate column definitions from query results
   const columnDefs = useMemo<ColDef[]>(() => {
-    if (!queryResults?.columns) return [];
+    if (!current?.columns) return [];
     
     return [
       {
@@ -36,7 +91,7 @@ export default function ResultsPanel() {
         cellClass: "text-zinc-500 font-mono text-xs",
         suppressMovable: true,
       },
-      ...queryResults.columns.map((col) => ({
+      ...current.columns.map((col) => ({
         field: col,
         headerName: col,
         sortable: true,
@@ -72,7 +127,7 @@ export default function ResultsPanel() {
         },
       })),
     ];
-  }, [queryResults?.columns]);
+  }, [current?.columns]);
 
   const defaultColDef = useMemo<ColDef>(() => ({
     sortable: true,
@@ -95,7 +150,7 @@ export default function ResultsPanel() {
     );
   }
 
-  if (!queryResults) {
+  if (!queryResults || !current) {
     return (
       <div className="h-full flex items-center justify-center bg-zinc-900">
         <div className="text-center">
@@ -109,16 +164,42 @@ export default function ResultsPanel() {
     );
   }
 
-  if (queryResults.error) {
+  const tabs = statements.length > 1 && (
+    <div className="flex items-center gap-1 px-2 py-1 border-b border-zinc-800 overflow-x-auto">
+      {statements.map((statement, i) => (
+        <button
+          key={i}
+          onClick={() => setQueryResults({ ...queryResults, selectedStatement: i })}
+          title={statement.statement}
+          className={`flex items-center gap-1.5 px-2 py-0.5 rounded text-xs whitespace-nowrap ${
+            i === selected ? "bg-zinc-800 text-zinc-200" : "text-zinc-500 hover:text-zinc-300"
+          }`}
+        >
+          {statement.error ? (
+            <AlertCircle className="w-3 h-3 text-red-400" />
+          ) : (
+            <CheckCircle className="w-3 h-3 text-green-400" />
+          )}
+          <span>Statement {i + 1}</span>
+          <span className="text-zinc-500">{statementSummary(statement)}</span>
+        </button>
+      ))}
+    </div>
+  );
+
+  if (current.error) {
     return (
-      <div className="h-full bg-zinc-900 p-4">
-        <div className="flex items-start gap-3 p-4 bg-red-900/20 border border-red-900/50 rounded-lg">
-          <AlertCircle className="w-5 h-5 text-red-400 flex-shrink-0 mt-0.5" />
-          <div>
-            <h3 className="text-red-400 font-medium">Query Error</h3>
-            <p className="text-red-300/80 text-sm mt-1 font-mono">
-              {queryResults.error}
-            </p>
+      <div className="h-full flex flex-col bg-zinc-900">
+        {tabs}
+        <div className="p-4">
+          <div className="flex items-start gap-3 p-4 bg-red-900/20 border border-red-900/50 rounded-lg">
+            <AlertCircle className="w-5 h-5 text-red-400 flex-shrink-0 mt-0.5" />
+            <div>
+              <h3 className="text-red-400 font-medium">Query Error</h3>
+              <p className="text-red-300/80 text-sm mt-1 font-mono">
+                {current.error}
+              </p>
+            </div>
           </div>
         </div>
       </div>
@@ -127,6 +208,7 @@ export default function ResultsPanel() {
 
   return (
     <div className="h-full flex flex-col bg-zinc-900">
+      {tabs}
       {/* Status Bar */}
       <div className="flex items-center justify-between px-4 py-2 border-b border-zinc-800 bg-zinc-900/80">
         <div className="flex items-center gap-4">
@@ -136,22 +218,45 @@ export default function ResultsPanel() {
           </div>
           <div className="flex items-center gap-1.5 text-zinc-500 text-sm">
             <Clock className="w-3.5 h-3.5" />
-            <span>{queryResults.executionTime}ms</span>
+            <span>{current.executionTime}ms</span>
           </div>
+          {queryResults.historyError && (
+            <span className="text-amber-400 text-xs" title={queryResults.historyError}>
+              Not saved to history
+            </span>
+          )}
         </div>
-        <div className="text-zinc-500 text-sm">
-          {queryResults.rowCount} row{queryResults.rowCount !== 1 ? "s" : ""}
+        <div className="flex items-center gap-3 text-zinc-500 text-sm">
+          {current.columns.length === 0 && current.rowsAffected != null ? (
+            <span>
+              {current.rowsAffected} row{current.rowsAffected !== 1 ? "s" : ""} affected
+            </span>
+          ) : (
+            <span>
+              {current.rowCount}
+              {current.truncated ? "+" : ""} row{current.rowCount !== 1 ? "s" : ""}
+            </span>
+          )}
+          {current.hasMore && (
+            <button
+              onClick={handleLoadMore}
+              disabled={isLoadingMore}
+              className="px-2 py-0.5 rounded bg-zinc-800 hover:bg-zinc-700 text-zinc-300 text-xs disabled:opacity-50"
+            >
+              {isLoadingMore ? "Loading..." : "Load more"}
+            </button>
+          )}
         </div>
       </div>
 
       {/* AG Grid Table */}
       <div className="flex-1 overflow-hidden">
-        {queryResults.columns.length === 0 ? (
+        {current.columns.length === 0 ? (
           <div className="p-4 text-zinc-500 text-sm">No columns returned</div>
         ) : (
           <AgGridReact
             theme={darkTheme}
-            rowData={queryResults.rows}
+            rowData={current.rows}
             columnDefs={columnDefs}
             defaultColDef={defaultColDef}
             onGridReady={onGridReady}
@@ -1,7 +1,8 @@
 import { useRef, useCallback } from "react";
 import Editor, { Monaco, OnMount } from "@monaco-editor/react";
 import { useAppStore } from "../../store/useAppStore";
-import { executeQuery } from "../../services/database";
+import { executeQuery, promptForParameters, toDisplayResult } from "../../services/database";
+import type { SourceLocation, StatementError } from "../../types";
 
 // eslint-disable-next-line @typescript-eslint/no-explicit-any
 type MonacoEditor = any;
@@ -209,6 +210,50 @@ export default function SqlEditor() {
     [databases]
   );
 
+  // Underlines where a statement failed. Locations are relative to the executed
+  // script, which starts at `scriptStart` in the editor model.
+  const markError = useCallback(
+    (script: string, scriptStart: number, details?: StatementError) => {
+      const model = editorRef.current?.getModel();
+      const monaco = monacoRef.current;
+      if (!model || !monaco) return;
+
+      const from = details?.position ?? details?.start;
+      if (!details || !from) {
+        monaco.editor.setModelMarkers(model, "query", []);
+        return;
+      }
+
+      const lines = script.split("\n");
+      const toPosition = (location: SourceLocation) => {
+        let offset = scriptStart;
+        for (let i = 0; i < location.line - 1 && i < lines.length; i++) {
+          offset += lines[i].length + 1;
+        }
+        return model.getPositionAt(offset + location.column - 1);
+      };
+
+      const start = toPosition(from);
+      // A server position points at one spot; underline the word there
+      const word = details.position ? model.getWordAtPosition(start) : null;
+      const end = details.position
+        ? { lineNumber: start.lineNumber, column: word ? word.endColumn : start.column + 1 }
+        : toPosition(details.end ?? from);
+
+      monaco.editor.setModelMarkers(model, "query", [
+        {
+          severity: monaco.MarkerSeverity.Error,
+          message: [details.message, details.detail, details.hint].filter(Boolean).join("\n"),
+          startLineNumber: start.lineNumber,
+          startColumn: start.column,
+          endLineNumber: end.lineNumber,
+          endColumn: end.column,
+        },
+      ]);
+    },
+    []
+  );
+
   const handleExecuteQuery = useCallback(async () => {
     if (!editorRef.current) return;
 
@@ -240,15 +285,18 @@ export default function SqlEditor() {
 
     setIsExecutingQuery(true);
 
+    const rangeStart =
+      selection && !selection.isEmpty() ? model.getOffsetAt(selection.getStartPosition()) : 0;
+    const scriptStart = rangeStart + (query.length - query.trimStart().length);
+
     try {
-      const result = await executeQuery(activeConnection, query.trim());
+      const parameters = await promptForParameters(activeConnection, query.trim());
+      if (!parameters) return;
+      const response = await executeQuery(activeConnection, query.trim(), undefined, undefined, parameters);
 
-      setQueryResults({
-        columns: result.columns,
-        rows: result.rows,
-        rowCount: result.rowCount,
-        executionTime: result.executionTime,
-      });
+      const display = toDisplayResult(response);
+      markError(query.trim(), scriptStart, display.errorDetails);
+      setQueryResults(display);
     } catch (error) {
       setQueryResults({
         columns: [],
@@ -260,7 +308,7 @@ export default function SqlEditor() {
     } finally {
       setIsExecutingQuery(false);
     }
-  }, [activeConnection, setQueryResults, setIsExecutingQuery]);
+  }, [activeConnection, setQueryResults, setIsExecutingQuery, markError]);
 
   const handleEditorChange = useCallback(
     (value: string | undefined) => {
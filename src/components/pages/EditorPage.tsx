@@ -1,7 +1,7 @@
 import { useEffect, useCallback } from "react";
 import { ArrowLeft, Play, Settings, Database, Save, FolderOpen } from "lucide-react";
 import { useAppStore } from "../../store/useAppStore";
-import { executeQuery } from "../../services/database";
+import { executeQuery, promptForParameters, toDisplayResult } from "../../services/database";
 import { openFileDialog, saveFile, createDirectory } from "../../services/files";
 import Sidebar from "../editor/Sidebar";
 import EditorTabBar from "../editor/EditorTabBar";
@@ -157,14 +157,11 @@ export default function EditorPage() {
     setIsExecutingQuery(true);
 
     try {
-      const result = await executeQuery(activeConnection, query);
+      const parameters = await promptForParameters(activeConnection, query);
+      if (!parameters) return;
+      const response = await executeQuery(activeConnection, query, undefined, undefined, parameters);
 
-      setQueryResults({
-        columns: result.columns,
-        rows: result.rows,
-        rowCount: result.rowCount,
-        executionTime: result.executionTime,
-      });
+      setQueryResults(toDisplayResult(response));
     } catch (error) {
       setQueryResults({
         columns: [],
@@ -1,7 +1,24 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { SavedConnection, DatabaseInfo, QueryResult } from "../types";
+import type {
+  SavedConnection,
+  DatabaseInfo,
+  DatabaseEntry,
+  DatabaseRoutine,
+  DatabaseTable,
+  DatabaseTrigger,
+  HistoryEntry,
+  QueryResult,
+  QueryResponse,
+  QueryParameter,
+  QueryPlan,
+  ResultPage,
+  SchemaEntry,
+  SessionInfo,
+  TableEntry,
+} from "../types";
 
 export interface ConnectionParams {
+  connectionId?: string;
   dbType: string;
   host?: string;
   port?: string;
@@ -9,10 +26,13 @@ export interface ConnectionParams {
   user?: string;
   password?: string;
   connectionString?: string;
+  includeSchemas?: string[];
+  excludeSchemas?: string[];
 }
 
 function connectionToParams(conn: SavedConnection): ConnectionParams {
   return {
+    connectionId: conn.id,
     dbType: conn.dbType,
     host: conn.host,
     port: conn.port,
@@ -20,6 +40,8 @@ function connectionToParams(conn: SavedConnection): ConnectionParams {
     user: conn.user,
     password: conn.password,
     connectionString: conn.connectionString,
+    includeSchemas: conn.includeSchemas,
+    excludeSchemas: conn.excludeSchemas,
   };
 }
 
@@ -28,15 +50,233 @@ export async function testConnection(conn: SavedConnection): Promise<string> {
   return invoke<string>("test_connection_params", { params });
 }
 
+export async function connect(conn: SavedConnection): Promise<SessionInfo> {
+  const params = connectionToParams(conn);
+  return invoke<SessionInfo>("connect", { params });
+}
+
+export async function disconnect(connectionId: string): Promise<boolean> {
+  return invoke<boolean>("disconnect", { connectionId });
+}
+
+// Transactions run on the session's pinned connection; the returned session
+// reports whether work is still uncommitted.
+export async function beginTransaction(connectionId: string): Promise<SessionInfo> {
+  return invoke<SessionInfo>("begin_transaction", { connectionId });
+}
+
+export async function commit(connectionId: string): Promise<SessionInfo> {
+  return invoke<SessionInfo>("commit", { connectionId });
+}
+
+export async function rollback(connectionId: string): Promise<SessionInfo> {
+  return invoke<SessionInfo>("rollback", { connectionId });
+}
+
+export async function listSessions(): Promise<SessionInfo[]> {
+  return invoke<SessionInfo[]>("list_sessions");
+}
+
 export async function getDatabases(conn: SavedConnection): Promise<DatabaseInfo[]> {
   const params = connectionToParams(conn);
   return invoke<DatabaseInfo[]>("get_databases", { params });
 }
 
+// Explorer tree, one level per call so expanding a node only loads that node
+export async function listDatabases(conn: SavedConnection): Promise<DatabaseEntry[]> {
+  const params = connectionToParams(conn);
+  return invoke<DatabaseEntry[]>("list_databases", { params });
+}
+
+export async function listSchemas(conn: SavedConnection, database: string): Promise<SchemaEntry[]> {
+  const params = connectionToParams(conn);
+  return invoke<SchemaEntry[]>("list_schemas", { params, database });
+}
+
+export async function listTables(
+  conn: SavedConnection,
+  database: string,
+  schema: string
+): Promise<TableEntry[]> {
+  const params = connectionToParams(conn);
+  return invoke<TableEntry[]>("list_tables", { params, database, schema });
+}
+
+export async function describeTable(
+  conn: SavedConnection,
+  database: string,
+  schema: string,
+  table: string
+): Promise<DatabaseTable> {
+  const params = connectionToParams(conn);
+  return invoke<DatabaseTable>("describe_table", { params, database, schema, table });
+}
+
+export async function listRoutines(
+  conn: SavedConnection,
+  database: string,
+  schema: string
+): Promise<DatabaseRoutine[]> {
+  const params = connectionToParams(conn);
+  return invoke<DatabaseRoutine[]>("list_routines", { params, database, schema });
+}
+
+export async function listTriggers(
+  conn: SavedConnection,
+  database: string,
+  schema: string
+): Promise<DatabaseTrigger[]> {
+  const params = connectionToParams(conn);
+  return invoke<DatabaseTrigger[]>("list_triggers", { params, database, schema });
+}
+
+// Full CREATE statement of a routine or trigger
+export async function getRoutineDefinition(
+  conn: SavedConnection,
+  database: string,
+  routine: DatabaseRoutine
+): Promise<string> {
+  const params = connectionToParams(conn);
+  return invoke<string>("get_definition", {
+    params,
+    database,
+    schema: routine.schema ?? database,
+    kind: routine.kind,
+    name: routine.name,
+    arguments: routine.arguments,
+  });
+}
+
+export async function getTriggerDefinition(
+  conn: SavedConnection,
+  database: string,
+  trigger: DatabaseTrigger
+): Promise<string> {
+  const params = connectionToParams(conn);
+  return invoke<string>("get_definition", {
+    params,
+    database,
+    schema: trigger.schema ?? database,
+    kind: "trigger",
+    name: trigger.name,
+    table: trigger.table,
+  });
+}
+
 export async function executeQuery(
+  conn: SavedConnection,
+  query: string,
+  queryId?: string,
+  pageSize?: number,
+  parameters?: Record<string, unknown>
+): Promise<QueryResponse> {
+  const params = connectionToParams(conn);
+  return invoke<QueryResponse>("execute_query", { params, query, queryId, pageSize, parameters });
+}
+
+// Placeholders the script needs values for, keyed the way executeQuery expects them
+export async function getQueryParameters(
+  dbType: string,
+  query: string
+): Promise<QueryParameter[]> {
+  return invoke<QueryParameter[]>("get_query_parameters", { dbType, query });
+}
+
+// With analyze the statement really runs (and is rolled back); anything but a query
+// is refused unless allowWrites is set. Pass a queryId to be able to cancel it.
+export async function explainQuery(
+  conn: SavedConnection,
+  query: string,
+  analyze = false,
+  allowWrites = false,
+  queryId?: string
+): Promise<QueryPlan> {
+  const params = connectionToParams(conn);
+  return invoke<QueryPlan>("explain_query", { params, query, analyze, allowWrites, queryId });
+}
+
+// Asks for a value for each placeholder in the script. Input that parses as JSON
+// (numbers, true/false, null) keeps its type; anything else is sent as text.
+// Returns null if the user cancels.
+export async function promptForParameters(
   conn: SavedConnection,
   query: string
-): Promise<QueryResult> {
+): Promise<Record<string, unknown> | null> {
+  const required = await getQueryParameters(conn.dbType, query);
+  const values: Record<string, unknown> = {};
+  for (const param of required) {
+    const input = window.prompt(`Value for ${param.name}`);
+    if (input === null) return null;
+    try {
+      values[param.name] = JSON.parse(input);
+    } catch {
+      values[param.name] = input;
+    }
+  }
+  return values;
+}
+
+// Keeps every statement's result for the results panel, opening on the failing
+// one if the script stopped early, otherwise the last one that returned a result set.
+export function toDisplayResult(response: QueryResponse): QueryResult {
+  const results = response.results;
+  const failed = results.findIndex((r) => r.error);
+  let selected = failed;
+  if (selected < 0) {
+    selected = results.map((r) => r.columns.length > 0).lastIndexOf(true);
+  }
+  if (selected < 0) {
+    selected = results.length - 1;
+  }
+  const shown = results[selected];
+
+  return {
+    columns: shown?.columns ?? [],
+    columnTypes: shown?.columnTypes ?? [],
+    rows: shown?.rows ?? [],
+    rowCount: shown?.rowCount ?? 0,
+    rowsAffected: shown?.rowsAffected,
+    executionTime: response.executionTime,
+    error: shown?.error,
+    errorDetails: results[failed]?.errorDetails,
+    queryId: response.queryId,
+    truncated: shown?.truncated ?? false,
+    hasMore: shown?.hasMore ?? false,
+    statements: results,
+    selectedStatement: Math.max(selected, 0),
+    historyError: response.historyError ?? undefined,
+  };
+}
+
+export async function searchQueryHistory(
+  search?: string,
+  connectionId?: string,
+  limit?: number
+): Promise<HistoryEntry[]> {
+  return invoke<HistoryEntry[]>("search_query_history", { search, connectionId, limit });
+}
+
+export async function rerunQueryHistory(
+  conn: SavedConnection,
+  entryId: number,
+  queryId?: string
+): Promise<QueryResponse> {
   const params = connectionToParams(conn);
-  return invoke<QueryResult>("execute_query", { params, query });
+  return invoke<QueryResponse>("rerun_query_history", { params, entryId, queryId });
+}
+
+export async function clearQueryHistory(connectionId?: string): Promise<number> {
+  return invoke<number>("clear_query_history", { connectionId });
+}
+
+export async function fetchMore(queryId: string, n: number): Promise<ResultPage> {
+  return invoke<ResultPage>("fetch_more", { queryId, n });
+}
+
+export async function closeCursor(queryId: string): Promise<boolean> {
+  return invoke<boolean>("close_cursor", { queryId });
+}
+
+export async function cancelQuery(queryId: string): Promise<boolean> {
+  return invoke<boolean>("cancel_query", { queryId });
 }
@@ -8,6 +8,7 @@ import type {
   EditorTab,
   QueryResult,
   DatabaseInfo,
+  DatabaseTable,
   DiagramSettings,
 } from "../types";
 
@@ -27,6 +28,8 @@ interface AppState {
   // Database Explorer
   databases: DatabaseInfo[];
   setDatabases: (databases: DatabaseInfo[]) => void;
+  // Adds or replaces tables of one database as the explorer loads them
+  setDatabaseTables: (databaseName: string, tables: DatabaseTable[]) => void;
   expandedNodes: Set<string>;
   toggleNode: (nodeId: string) => void;
 
@@ -98,6 +101,15 @@ export const useAppStore = create<AppState>()(
       // Database Explorer
       databases: [],
       setDatabases: (databases) => set({ databases }),
+      setDatabaseTables: (databaseName, tables) =>
+        set((state) => ({
+          databases: state.databases.map((db) => {
+            if (db.name !== databaseName) return db;
+            const replaced = (t: DatabaseTable) =>
+              tables.some((n) => n.name === t.name && n.schema === t.schema);
+            return { ...db, tables: [...(db.tables ?? []).filter((t) => !replaced(t)), ...tables] };
+          }),
+        })),
       expandedNodes: new Set(),
       toggleNode: (nodeId) =>
         set((state) => {
@@ -3,17 +3,48 @@ export interface Column {
   type: string;
   isPrimaryKey: boolean;
   isForeignKey: boolean;
+  isUnique?: boolean;
   foreignKeyTargetTable?: string;
   foreignKeyTargetColumn?: string;
+  // Only set when the target is in a different schema
+  foreignKeyTargetSchema?: string;
+  // Labels of an enum-typed column, in declaration order
+  enumValues?: string[];
+  comment?: string;
   isMultivalued?: boolean;
   isDerived?: boolean;
   cardinalitySource?: string;
   cardinalityTarget?: string;
 }
 
+export type TableKind = "table" | "view" | "materialized_view";
+
+export interface TableRef {
+  schema?: string;
+  name: string;
+}
+
+export interface ForeignKey {
+  name?: string;
+  columns: string[];
+  // Only set when the target is in a different schema
+  targetSchema?: string;
+  targetTable: string;
+  // Paired with columns by position
+  targetColumns: string[];
+  onDelete?: string;
+  onUpdate?: string;
+}
+
 export interface Table {
   name: string;
+  schema?: string;
+  kind?: TableKind;
+  // Tables and views a view reads from
+  dependsOn?: TableRef[];
   columns: Column[];
+  foreignKeys?: ForeignKey[];
+  comment?: string;
 }
 
 export interface Schema {
@@ -26,6 +57,10 @@ export interface MermaidConfig {
   randomize?: boolean;
   direction?: "TD" | "LR";
   compact?: boolean;
+  // Lists enum labels next to enum-typed attributes
+  showEnumValues?: boolean;
+  // Table and column comments as tooltips (chen) or attribute comments (crow's foot)
+  showComments?: boolean;
 }
 
 export interface GenerateRequest {
@@ -34,11 +69,28 @@ export interface GenerateRequest {
   sql?: string;
   style?: "crows_foot" | "chen";
   config?: MermaidConfig;
+  includeSchemas?: string[];
+  excludeSchemas?: string[];
+  // Dialect of `sql`; "auto" (the default) tries each in turn
+  dialect?: SqlDialect;
+  // Skip statements that fail to parse instead of failing (the default)
+  tolerant?: boolean;
 }
 
+export type SqlDialect = "auto" | "mysql" | "postgres" | "sqlite" | "mssql" | "generic";
+
 export interface GenerateResponse {
   mermaidCode: string;
   schema: Schema;
+  // Statements of a SQL source that couldn't be applied to the schema
+  warnings?: SchemaWarning[];
+  // Dialect a SQL source was parsed with
+  dialect?: SqlDialect;
+}
+
+export interface SchemaWarning {
+  message: string;
+  location?: SourceLocation;
 }
 
 export interface SavedConnection {
@@ -52,6 +104,9 @@ export interface SavedConnection {
   database?: string;
   user?: string;
   password?: string;
+  // Postgres schemas to show; system schemas are always hidden
+  includeSchemas?: string[];
+  excludeSchemas?: string[];
   style?: "crows_foot" | "chen";
   theme?: string;
   curve?: string;
@@ -68,7 +123,51 @@ export interface DatabaseConnection extends SavedConnection {
 export interface DatabaseTable {
   name: string;
   schema?: string;
+  kind: TableKind;
+  // The SELECT behind a view or materialized view
+  definition?: string;
+  dependsOn: TableRef[];
   columns: DatabaseColumn[];
+  foreignKeys: ForeignKey[];
+  indexes: DatabaseIndex[];
+  uniqueConstraints: UniqueConstraint[];
+  checkConstraints: CheckConstraint[];
+  comment?: string;
+  // Not set for views
+  stats?: TableStats;
+}
+
+// Planner estimates and on-disk sizes in bytes
+export interface TableStats {
+  // Missing when the table has never been analyzed
+  rowEstimate?: number;
+  totalSize: number;
+  tableSize: number;
+  indexSize: number;
+  // Milliseconds since the epoch; Postgres only
+  lastVacuum?: number;
+  lastAnalyze?: number;
+}
+
+export interface DatabaseIndex {
+  name: string;
+  // Key columns in index order; expressions are given as their SQL text
+  columns: string[];
+  isUnique: boolean;
+  isPrimary: boolean;
+  method?: string;
+  // WHERE clause of a partial index
+  predicate?: string;
+}
+
+export interface UniqueConstraint {
+  name: string;
+  columns: string[];
+}
+
+export interface CheckConstraint {
+  name: string;
+  expression: string;
 }
 
 export interface DatabaseColumn {
@@ -77,7 +176,13 @@ export interface DatabaseColumn {
   nullable: boolean;
   isPrimaryKey: boolean;
   isForeignKey: boolean;
+  foreignKeyTargetTable?: string;
+  foreignKeyTargetColumn?: string;
+  // Only set when the target is in a different schema
+  foreignKeyTargetSchema?: string;
+  enumValues: string[];
   defaultValue?: string;
+  comment?: string;
 }
 
 export interface DatabaseSchema {
@@ -89,6 +194,84 @@ export interface DatabaseInfo {
   name: string;
   schemas?: DatabaseSchema[];
   tables?: DatabaseTable[];
+  enums?: EnumType[];
+  domains?: DomainType[];
+  sequences?: DatabaseSequence[];
+  routines?: DatabaseRoutine[];
+  triggers?: DatabaseTrigger[];
+  // Why the database couldn't be read
+  error?: string;
+}
+
+export interface EnumType {
+  schema?: string;
+  name: string;
+  labels: string[];
+}
+
+export interface DomainType {
+  schema?: string;
+  name: string;
+  baseType: string;
+  notNull: boolean;
+  defaultValue?: string;
+  constraints: CheckConstraint[];
+}
+
+export interface DatabaseRoutine {
+  schema?: string;
+  name: string;
+  kind: "function" | "procedure";
+  // Argument list as declared; tells overloads apart
+  arguments: string;
+  returnType?: string;
+  language: string;
+  body?: string;
+}
+
+export interface DatabaseTrigger {
+  schema?: string;
+  name: string;
+  table: string;
+  timing: string;
+  events: string[];
+  level: string;
+  // Postgres: the trigger function; MySQL: the statement it runs
+  function?: string;
+  body?: string;
+  enabled: boolean;
+}
+
+export interface DatabaseSequence {
+  schema?: string;
+  name: string;
+  dataType: string;
+  startValue: number;
+  increment: number;
+  // The column the sequence belongs to: serial, identity or OWNED BY
+  ownedBy?: {
+    schema?: string;
+    table: string;
+    column: string;
+  };
+}
+
+// Explorer nodes, listed before their contents are loaded. `error` is set when a
+// node is there but can't be opened, e.g. without CONNECT or USAGE privilege.
+export interface DatabaseEntry {
+  name: string;
+  error?: string;
+}
+
+export interface SchemaEntry {
+  name: string;
+  error?: string;
+}
+
+export interface TableEntry {
+  name: string;
+  schema?: string;
+  kind: TableKind;
 }
 
 // Project Manager Types
@@ -111,6 +294,15 @@ export interface Project {
   updatedAt: Date;
 }
 
+// Open backend connection sessions
+export interface SessionInfo {
+  id: string;
+  dbType: string;
+  database?: string;
+  connectedAt: number;
+  inTransaction: boolean;
+}
+
 // Query Results
 export interface QueryResult {
   columns: string[];
@@ -118,6 +310,113 @@ export interface QueryResult {
   rowCount: number;
   executionTime: number;
   error?: string;
+  queryId?: string;
+  columnTypes?: ResultColumn[];
+  errorDetails?: StatementError;
+  truncated?: boolean;
+  hasMore?: boolean;
+  rowsAffected?: number;
+  // Every statement of the script, shown as tabs, and the one on display
+  statements?: StatementResult[];
+  selectedStatement?: number;
+  historyError?: string;
+}
+
+export interface ResultColumn {
+  name: string;
+  dbType: string;
+  nullable?: boolean;
+}
+
+// 1-based line and column in the executed script
+export interface SourceLocation {
+  line: number;
+  column: number;
+}
+
+export interface StatementError {
+  message: string;
+  statementIndex: number;
+  start?: SourceLocation;
+  end?: SourceLocation;
+  code?: string;
+  errorNumber?: number;
+  detail?: string;
+  hint?: string;
+  position?: SourceLocation;
+}
+
+// One statement of an executed script, as returned by the backend
+export interface StatementResult {
+  statement: string;
+  columns: string[];
+  columnTypes: ResultColumn[];
+  rows: Record<string, unknown>[];
+  rowCount: number;
+  rowsAffected?: number;
+  executionTime: number;
+  error?: string;
+  errorDetails?: StatementError;
+  truncated: boolean;
+  hasMore: boolean;
+}
+
+export interface QueryResponse {
+  queryId: string;
+  results: StatementResult[];
+  executionTime: number;
+  inTransaction: boolean;
+  // Set when the run couldn't be saved to the query history
+  historyError?: string;
+}
+
+// Further rows of a result set left open by execute_query
+export interface ResultPage {
+  queryId: string;
+  rows: Record<string, unknown>[];
+  rowCount: number;
+  hasMore: boolean;
+}
+
+// One executed query, as kept in the query history
+export interface HistoryEntry {
+  id: number;
+  timestamp: number;
+  connectionId: string;
+  sql: string;
+  parameters?: Record<string, unknown>;
+  duration: number;
+  rowCount: number;
+  error?: string;
+}
+
+// Placeholder found in a script: ":name", "$1", or "?" numbered as "?1", "?2", ...
+export interface QueryParameter {
+  name: string;
+  kind: "named" | "positional";
+}
+
+// EXPLAIN plan, normalised across Postgres and MariaDB/MySQL
+export interface PlanNode {
+  operation: string;
+  relation?: string;
+  startupCost?: number;
+  cost?: number;
+  rows?: number;
+  actualRows?: number;
+  actualTime?: number;
+  loops?: number;
+  details: Record<string, unknown>;
+  children: PlanNode[];
+}
+
+export interface QueryPlan {
+  dbType: string;
+  analyzed: boolean;
+  root: PlanNode;
+  planningTime?: number;
+  executionTime?: number;
+  raw: unknown;
 }
 
 // Editor Tab
@@ -138,6 +437,8 @@ export interface EditorTab {
   diagramRandomize?: boolean;
   diagramDirection?: "TD" | "LR";
   diagramCompact?: boolean;
+  diagramEnumValues?: boolean;
+  diagramComments?: boolean;
   diagramBackground?: string;
   schema?: Schema;
 }
@@ -150,6 +451,8 @@ export interface DiagramSettings {
   randomize: boolean;
   direction: "TD" | "LR";
   compact: boolean;
+  showEnumValues?: boolean;
+  showComments?: boolean;
   background: string;
 }
 